@@ -10,6 +10,8 @@ pub struct BitboardUtils;
 
 impl BitboardUtils {
 	pub const EMPTY: Bitboard = 0;
+	pub const LIGHT_SQUARES: Bitboard = 0x55AA55AA55AA55AA;
+	pub const DARK_SQUARES: Bitboard = !Self::LIGHT_SQUARES;
 
 	#[rustfmt::skip]
 	pub const FILES: [Bitboard; FileUtils::SIZE] = [
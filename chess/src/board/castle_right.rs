@@ -38,12 +38,19 @@ impl CastleRightUtils {
 
 impl CastleRightUtils {
 	pub fn parse(value: char) -> CastleRight {
+		match Self::try_parse(value) {
+			Some(castle_right) => castle_right,
+			None => panic!("Invalid castle right: {}", value),
+		}
+	}
+
+	pub fn try_parse(value: char) -> Option<CastleRight> {
 		match value {
-			'K' => Self::WHITE_KING,
-			'Q' => Self::WHITE_QUEEN,
-			'k' => Self::BLACK_KING,
-			'q' => Self::BLACK_QUEEN,
-			_ => panic!("Invalid castle right: {}", value),
+			'K' => Some(Self::WHITE_KING),
+			'Q' => Some(Self::WHITE_QUEEN),
+			'k' => Some(Self::BLACK_KING),
+			'q' => Some(Self::BLACK_QUEEN),
+			_ => None,
 		}
 	}
 
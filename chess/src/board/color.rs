@@ -15,10 +15,17 @@ impl ColorUtils {
 
 impl ColorUtils {
 	pub fn parse(value: char) -> Color {
+		match Self::try_parse(value) {
+			Some(color) => color,
+			None => panic!("Invalid color: {}", value),
+		}
+	}
+
+	pub fn try_parse(value: char) -> Option<Color> {
 		match value {
-			'w' | 'W' => Self::WHITE,
-			'b' | 'B' => Self::BLACK,
-			_ => panic!("Invalid color: {}", value),
+			'w' | 'W' => Some(Self::WHITE),
+			'b' | 'B' => Some(Self::BLACK),
+			_ => None,
 		}
 	}
 
@@ -0,0 +1,78 @@
+use super::{
+	file_rank::{FileUtils, RankUtils},
+	Rank,
+};
+use std::{error::Error, fmt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FenField {
+	Pieces,
+	Color,
+	CastleRights,
+	EnPassant,
+	HalfmoveClock,
+	FullmoveNumber,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FenError {
+	/// A required field is absent.
+	MissingField(FenField),
+	/// The character at `index` within `field` is not allowed there.
+	InvalidCharacter {
+		field: FenField,
+		index: usize,
+		character: char,
+	},
+	/// The piece placement does not describe exactly eight ranks.
+	InvalidRankCount(usize),
+	/// A rank of the piece placement does not describe exactly eight files.
+	InvalidRankLength { rank: Rank, length: usize },
+	/// A counter field is not a valid number.
+	InvalidNumber { field: FenField, value: String },
+}
+
+impl fmt::Display for FenField {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::Pieces => "piece placement",
+				Self::Color => "active color",
+				Self::CastleRights => "castling rights",
+				Self::EnPassant => "en passant square",
+				Self::HalfmoveClock => "halfmove clock",
+				Self::FullmoveNumber => "fullmove number",
+			}
+		)
+	}
+}
+
+impl fmt::Display for FenError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::MissingField(field) => write!(f, "missing {field}"),
+			Self::InvalidCharacter {
+				field,
+				index,
+				character,
+			} => write!(
+				f,
+				"invalid character '{character}' at index {index} of the {field}"
+			),
+			Self::InvalidRankCount(count) => {
+				write!(f, "expected {} ranks, found {count}", RankUtils::SIZE)
+			}
+			Self::InvalidRankLength { rank, length } => write!(
+				f,
+				"rank {} describes {length} files instead of {}",
+				RankUtils::to_char(*rank),
+				FileUtils::SIZE
+			),
+			Self::InvalidNumber { field, value } => write!(f, "invalid {field}: '{value}'"),
+		}
+	}
+}
+
+impl Error for FenError {}
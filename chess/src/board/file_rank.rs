@@ -21,16 +21,23 @@ impl RankUtils {
 
 impl RankUtils {
 	pub fn from_char(value: char) -> Rank {
+		match Self::try_from_char(value) {
+			Some(rank) => rank,
+			None => panic!("Invalid rank: {}", value),
+		}
+	}
+
+	pub fn try_from_char(value: char) -> Option<Rank> {
 		match value {
-			'1' => Self::R1,
-			'2' => Self::R2,
-			'3' => Self::R3,
-			'4' => Self::R4,
-			'5' => Self::R5,
-			'6' => Self::R6,
-			'7' => Self::R7,
-			'8' => Self::R8,
-			_ => panic!("Invalid rank: {}", value),
+			'1' => Some(Self::R1),
+			'2' => Some(Self::R2),
+			'3' => Some(Self::R3),
+			'4' => Some(Self::R4),
+			'5' => Some(Self::R5),
+			'6' => Some(Self::R6),
+			'7' => Some(Self::R7),
+			'8' => Some(Self::R8),
+			_ => None,
 		}
 	}
 
@@ -69,16 +76,23 @@ impl FileUtils {
 
 impl FileUtils {
 	pub fn from_char(value: char) -> File {
+		match Self::try_from_char(value) {
+			Some(file) => file,
+			None => panic!("Invalid file: {}", value),
+		}
+	}
+
+	pub fn try_from_char(value: char) -> Option<File> {
 		match value {
-			'a' => Self::A,
-			'b' => Self::B,
-			'c' => Self::C,
-			'd' => Self::D,
-			'e' => Self::E,
-			'f' => Self::F,
-			'g' => Self::G,
-			'h' => Self::H,
-			_ => panic!("Invalid file: {}", value),
+			'a' => Some(Self::A),
+			'b' => Some(Self::B),
+			'c' => Some(Self::C),
+			'd' => Some(Self::D),
+			'e' => Some(Self::E),
+			'f' => Some(Self::F),
+			'g' => Some(Self::G),
+			'h' => Some(Self::H),
+			_ => None,
 		}
 	}
 
@@ -8,7 +8,7 @@ use std::fmt;
 
 impl fmt::Display for Board {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		write!(f, "{}\n{}", self.board_string(), self.fen_string())
+		write!(f, "{}\n{}", self.board_string(), self.to_fen())
 	}
 }
 
@@ -50,7 +50,8 @@ impl Board {
 		board + "     a   b   c   d   e   f   g   h\n"
 	}
 
-	fn fen_string(&self) -> String {
+	/// Serializes the position in Forsyth-Edwards Notation.
+	pub fn to_fen(&self) -> String {
 		let mut pieces = String::new();
 
 		for rank in RankUtils::RANGE.rev() {
@@ -82,7 +83,10 @@ impl Board {
 		}
 
 		let color = ColorUtils::to_string(self.color);
-		let castle_rights = CastleRightUtils::to_string(self.castle_rights);
+		let castle_rights = match self.castle_rights {
+			CastleRightUtils::NONE => "-".to_string(),
+			castle_rights => CastleRightUtils::to_string(castle_rights),
+		};
 
 		let en_passant = match self.en_passant {
 			Some(square) => SquareUtils::to_string(square),
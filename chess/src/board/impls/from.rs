@@ -5,15 +5,43 @@ use castle_right::CastleRightUtils;
 use file_rank::{FileUtils, RankUtils};
 use piece::PieceUtils;
 use square::SquareUtils;
+use std::str::FromStr;
+use zobrist::ZobristUtils;
 
 impl From<&str> for Board {
 	fn from(value: &str) -> Self {
-		Self::from((value, Arc::new(HashTable::default())))
+		Self::from((value, HashTable::shared(ZobristUtils::DEFAULT_SEED)))
 	}
 }
 
 impl From<(&str, Arc<HashTable>)> for Board {
 	fn from(value: (&str, Arc<HashTable>)) -> Self {
+		match Self::from_fen_with_table(value.0, value.1) {
+			Ok(board) => board,
+			Err(error) => panic!("Invalid FEN: {error}"),
+		}
+	}
+}
+
+impl FromStr for Board {
+	type Err = FenError;
+
+	fn from_str(fen: &str) -> Result<Self, Self::Err> {
+		Self::from_fen(fen)
+	}
+}
+
+impl Board {
+	pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+		Self::from_fen_with_seed(fen, ZobristUtils::DEFAULT_SEED)
+	}
+
+	/// Parses a FEN, hashing the position with the Zobrist keys generated from `seed`.
+	pub fn from_fen_with_seed(fen: &str, seed: u64) -> Result<Self, FenError> {
+		Self::from_fen_with_table(fen, HashTable::shared(seed))
+	}
+
+	fn from_fen_with_table(fen: &str, hash_table: Arc<HashTable>) -> Result<Self, FenError> {
 		let mut board = Self {
 			pieces: [[BitboardUtils::EMPTY; PieceUtils::SIZE]; ColorUtils::SIZE],
 			color: ColorUtils::WHITE,
@@ -29,27 +57,28 @@ impl From<(&str, Arc<HashTable>)> for Board {
 
 			hash: ZobristHash::default(),
 
-			hash_table: value.1,
+			hash_table,
 		};
 
-		let tokens = value.0.split_whitespace().collect::<Vec<&str>>();
+		let mut tokens = fen.split_whitespace();
+		let mut next = |field| tokens.next().ok_or(FenError::MissingField(field));
 
-		BoardBuilder::set_pieces(&mut board, tokens[0]);
-		BoardBuilder::set_color(&mut board, tokens[1]);
-		BoardBuilder::set_castling_rights(&mut board, tokens[2]);
-		BoardBuilder::set_en_passant(&mut board, tokens[3]);
+		BoardBuilder::set_pieces(&mut board, next(FenField::Pieces)?)?;
+		BoardBuilder::set_color(&mut board, next(FenField::Color)?)?;
+		BoardBuilder::set_castling_rights(&mut board, next(FenField::CastleRights)?)?;
+		BoardBuilder::set_en_passant(&mut board, next(FenField::EnPassant)?)?;
 
-		if let Some(num) = tokens.get(4) {
-			board.halfmove_clock = num.parse().unwrap_or(0);
+		if let Ok(num) = next(FenField::HalfmoveClock) {
+			board.halfmove_clock = BoardBuilder::parse_number(FenField::HalfmoveClock, num)?;
 		}
 
-		if let Some(num) = tokens.get(5) {
-			board.fullmove_number = num.parse().unwrap_or(1);
+		if let Ok(num) = next(FenField::FullmoveNumber) {
+			board.fullmove_number = BoardBuilder::parse_number(FenField::FullmoveNumber, num)?;
 		}
 
 		board.hash = board.init_hash();
 
-		board
+		Ok(board)
 	}
 }
 
@@ -79,7 +108,7 @@ impl Board {
 
 		hash ^= self.hash_table.color(self.color);
 		hash ^= self.hash_table.castle(self.castle_rights);
-		hash ^= self.hash_table.en_passant(self.en_passant);
+		hash ^= self.en_passant_key();
 
 		hash
 	}
@@ -88,52 +117,128 @@ impl Board {
 struct BoardBuilder;
 
 impl BoardBuilder {
-	fn set_pieces(board: &mut Board, pieces: &str) {
+	fn set_pieces(board: &mut Board, pieces: &str) -> Result<(), FenError> {
+		let ranks = pieces.split('/').count();
+
+		if ranks != RankUtils::SIZE {
+			return Err(FenError::InvalidRankCount(ranks));
+		}
+
 		let mut rank = RankUtils::R8;
 		let mut file = FileUtils::A;
 
-		for ch in pieces.chars() {
+		for (index, ch) in pieces.char_indices() {
 			match ch {
 				'/' => {
+					if file != FileUtils::SIZE {
+						return Err(FenError::InvalidRankLength { rank, length: file });
+					}
+
 					rank -= 1;
 					file = FileUtils::A;
 				}
-				'1'..='8' => {
-					for _ in 0..ch.to_digit(10).unwrap() {
-						file += 1;
-					}
-				}
+				'1'..='8' => file += ch.to_digit(10).unwrap() as usize,
 				_ => {
-					board.add_piece(
-						PieceUtils::parse(ch),
-						ColorUtils::from_bool(ch.is_uppercase()),
-						SquareUtils::from_location(file, rank),
-					);
+					let piece = PieceUtils::try_parse(ch).ok_or(FenError::InvalidCharacter {
+						field: FenField::Pieces,
+						index,
+						character: ch,
+					})?;
+
+					if file < FileUtils::SIZE {
+						board.add_piece(
+							piece,
+							ColorUtils::from_bool(ch.is_uppercase()),
+							SquareUtils::from_location(file, rank),
+						);
+					}
+
 					file += 1;
 				}
 			}
+
+			if file > FileUtils::SIZE {
+				return Err(FenError::InvalidRankLength { rank, length: file });
+			}
+		}
+
+		if file != FileUtils::SIZE {
+			return Err(FenError::InvalidRankLength { rank, length: file });
 		}
+
+		Ok(())
 	}
 
-	fn set_color(board: &mut Board, color: &str) {
-		if let Some(ch) = color.chars().next() {
-			board.color = ColorUtils::parse(ch);
+	fn set_color(board: &mut Board, color: &str) -> Result<(), FenError> {
+		for (index, ch) in color.char_indices() {
+			board.color = match ColorUtils::try_parse(ch) {
+				Some(color) if index == 0 => color,
+				_ => {
+					return Err(FenError::InvalidCharacter {
+						field: FenField::Color,
+						index,
+						character: ch,
+					})
+				}
+			};
 		}
+
+		Ok(())
 	}
 
-	fn set_castling_rights(board: &mut Board, castling_rights: &str) {
+	fn set_castling_rights(board: &mut Board, castling_rights: &str) -> Result<(), FenError> {
 		if castling_rights == "-" {
-			return;
+			return Ok(());
 		}
 
-		for ch in castling_rights.chars() {
-			board.castle_rights |= CastleRightUtils::parse(ch);
+		for (index, ch) in castling_rights.char_indices() {
+			board.castle_rights |=
+				CastleRightUtils::try_parse(ch).ok_or(FenError::InvalidCharacter {
+					field: FenField::CastleRights,
+					index,
+					character: ch,
+				})?;
 		}
+
+		Ok(())
 	}
 
-	fn set_en_passant(board: &mut Board, en_passant: &str) {
-		if en_passant != "-" {
-			board.en_passant = Some(SquareUtils::parse(en_passant));
+	fn set_en_passant(board: &mut Board, en_passant: &str) -> Result<(), FenError> {
+		if en_passant == "-" {
+			return Ok(());
 		}
+
+		let invalid = |index: usize| {
+			let character = en_passant.chars().nth(index).unwrap_or(' ');
+
+			FenError::InvalidCharacter {
+				field: FenField::EnPassant,
+				index,
+				character,
+			}
+		};
+
+		let mut chars = en_passant.chars();
+
+		let file = chars.next().and_then(FileUtils::try_from_char);
+		let rank = chars.next().and_then(RankUtils::try_from_char);
+
+		match (file, rank) {
+			(None, _) => Err(invalid(0)),
+			(_, None) => Err(invalid(1)),
+			(_, _) if chars.next().is_some() => Err(invalid(2)),
+			(Some(file), Some(rank)) => {
+				board.en_passant = Some(SquareUtils::from_location(file, rank));
+
+				Ok(())
+			}
+		}
+	}
+
+	fn parse_number<T: FromStr>(field: FenField, value: &str) -> Result<T, FenError> {
+		value.parse().map_err(|_| FenError::InvalidNumber {
+			field,
+			value: value.to_string(),
+		})
 	}
 }
@@ -1,4 +1,6 @@
 mod display;
 mod from;
+mod polyglot;
+mod validate;
 
 use super::*;
@@ -0,0 +1,84 @@
+use super::*;
+
+use bitboard::BitboardUtils;
+use castle_right::CastleRightUtils;
+use square::SquareUtils;
+
+use crate::board::polyglot::PolyglotUtils;
+
+impl Board {
+	/// The key of the position in Polyglot opening books. Like `hash`, it only includes the en
+	/// passant file when a pawn of the side to move stands ready to capture.
+	pub fn polyglot_key(&self) -> u64 {
+		let mut key = 0;
+
+		for color in [ColorUtils::WHITE, ColorUtils::BLACK] {
+			for (piece, mut pieces) in self.pieces[color].into_iter().enumerate() {
+				while pieces > 0 {
+					let square = BitboardUtils::pop_lsb(&mut pieces);
+
+					key ^= PolyglotUtils::RANDOM[PolyglotUtils::piece(piece, color, square)];
+				}
+			}
+		}
+
+		let castle_rights = [
+			CastleRightUtils::WHITE_KING,
+			CastleRightUtils::WHITE_QUEEN,
+			CastleRightUtils::BLACK_KING,
+			CastleRightUtils::BLACK_QUEEN,
+		];
+
+		for (index, castle_right) in castle_rights.into_iter().enumerate() {
+			if self.castle_rights & castle_right != 0 {
+				key ^= PolyglotUtils::RANDOM[PolyglotUtils::CASTLING + index];
+			}
+		}
+
+		if let Some(en_passant) = self
+			.en_passant
+			.filter(|&square| self.en_passant_capturable(square))
+		{
+			key ^= PolyglotUtils::RANDOM
+				[PolyglotUtils::EN_PASSANT + SquareUtils::location(en_passant).0];
+		}
+
+		if self.color == ColorUtils::WHITE {
+			key ^= PolyglotUtils::RANDOM[PolyglotUtils::TURN];
+		}
+
+		key
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::Chess;
+
+	/// The example keys of the Polyglot book format specification.
+	#[test]
+	fn reference_keys() {
+		let cases = [
+			("", 0x463b96181691fc9c),
+			("e2e4", 0x823c9b50fd114196),
+			("e2e4 d7d5", 0x0756b94461c50fb0),
+			("e2e4 d7d5 e4e5", 0x662fafb965db29d4),
+			("e2e4 d7d5 e4e5 f7f5", 0x22a48b5a8e47ff78),
+			("e2e4 d7d5 e4e5 f7f5 e1e2", 0x652a607ca3f242c1),
+			("e2e4 d7d5 e4e5 f7f5 e1e2 e8f7", 0x00fdd303c946bdd9),
+			("a2a4 b7b5 h2h4 b5b4 c2c4", 0x3c8123ea7b067637),
+			("a2a4 b7b5 h2h4 b5b4 c2c4 b4c3 a1a3", 0x5c3f9b829b279560),
+		];
+
+		for (moves, key) in cases {
+			let mut chess = Chess::default();
+
+			for text in moves.split_whitespace() {
+				let m = chess.parse_uci_move(text).unwrap();
+				chess.make_move(m);
+			}
+
+			assert_eq!(chess.board.polyglot_key(), key, "{moves}");
+		}
+	}
+}
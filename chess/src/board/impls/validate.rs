@@ -0,0 +1,135 @@
+use super::*;
+
+use castle_right::CastleRightUtils;
+use file_rank::RankUtils;
+use piece::PieceUtils;
+use square::SquareUtils;
+
+use crate::{move_gen::MoveGen, Chess};
+
+const CASTLE_SQUARES: [(CastleRight, Color, Square, Square); 4] = [
+	(
+		CastleRightUtils::WHITE_KING,
+		ColorUtils::WHITE,
+		SquareUtils::E1,
+		SquareUtils::H1,
+	),
+	(
+		CastleRightUtils::WHITE_QUEEN,
+		ColorUtils::WHITE,
+		SquareUtils::E1,
+		SquareUtils::A1,
+	),
+	(
+		CastleRightUtils::BLACK_KING,
+		ColorUtils::BLACK,
+		SquareUtils::E8,
+		SquareUtils::H8,
+	),
+	(
+		CastleRightUtils::BLACK_QUEEN,
+		ColorUtils::BLACK,
+		SquareUtils::E8,
+		SquareUtils::A8,
+	),
+];
+
+impl Board {
+	/// Checks that the position could arise in a legal game, reporting every inconsistency.
+	pub fn validate(&self, move_gen: &MoveGen) -> Result<(), Vec<ValidationIssue>> {
+		let mut issues = Vec::new();
+
+		for color in ColorUtils::RANGE {
+			let kings = self.pieces[color][PieceUtils::KING].count_ones();
+			let pawns = self.pieces[color][PieceUtils::PAWN].count_ones();
+			let pieces = self.occupancy_color[color].count_ones();
+
+			if kings != 1 {
+				issues.push(ValidationIssue::KingCount {
+					color,
+					count: kings,
+				});
+			}
+
+			if pieces > 16 {
+				issues.push(ValidationIssue::TooManyPieces {
+					color,
+					count: pieces,
+				});
+			}
+
+			if pawns > 8 {
+				issues.push(ValidationIssue::TooManyPawns {
+					color,
+					count: pawns,
+				});
+			}
+		}
+
+		let pawns = self.pieces[ColorUtils::WHITE][PieceUtils::PAWN]
+			| self.pieces[ColorUtils::BLACK][PieceUtils::PAWN];
+		let mut back_rank_pawns =
+			pawns & (BitboardUtils::RANKS[RankUtils::R1] | BitboardUtils::RANKS[RankUtils::R8]);
+
+		while back_rank_pawns > 0 {
+			let square = BitboardUtils::pop_lsb(&mut back_rank_pawns);
+
+			issues.push(ValidationIssue::PawnOnBackRank(square));
+		}
+
+		for (castle_right, color, king, rook) in CASTLE_SQUARES {
+			let in_place = BitboardUtils::occupied(self.pieces[color][PieceUtils::KING], king)
+				&& BitboardUtils::occupied(self.pieces[color][PieceUtils::ROOK], rook);
+
+			if self.castle_rights & castle_right > 0 && !in_place {
+				issues.push(ValidationIssue::InvalidCastleRight(castle_right));
+			}
+		}
+
+		if let Some(square) = self.en_passant {
+			if !self.valid_en_passant(square) {
+				issues.push(ValidationIssue::InvalidEnPassant(square));
+			}
+		}
+
+		let opponent = self.color ^ 1;
+		let opponent_king = self.pieces[opponent][PieceUtils::KING];
+
+		if opponent_king.count_ones() == 1
+			&& move_gen.square_attacked(self, self.color, BitboardUtils::lsb(opponent_king))
+		{
+			issues.push(ValidationIssue::OpponentInCheck);
+		}
+
+		match issues.is_empty() {
+			true => Ok(()),
+			false => Err(issues),
+		}
+	}
+
+	fn valid_en_passant(&self, square: Square) -> bool {
+		let rank = match self.color {
+			ColorUtils::WHITE => RankUtils::R6,
+			_ => RankUtils::R3,
+		};
+
+		if SquareUtils::location(square).1 != rank {
+			return false;
+		}
+
+		let (pushed, origin) = match self.color {
+			ColorUtils::WHITE => (square - 8, square + 8),
+			_ => (square + 8, square - 8),
+		};
+
+		!BitboardUtils::occupied(self.occupancy, square)
+			&& !BitboardUtils::occupied(self.occupancy, origin)
+			&& BitboardUtils::occupied(self.pieces[self.color ^ 1][PieceUtils::PAWN], pushed)
+	}
+}
+
+impl Chess {
+	pub fn validate(&self) -> Result<(), Vec<ValidationIssue>> {
+		self.board.validate(&self.move_gen)
+	}
+}
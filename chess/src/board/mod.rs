@@ -4,15 +4,20 @@ mod prelude;
 pub mod bitboard;
 pub mod castle_right;
 pub mod color;
+pub mod fen;
 pub mod file_rank;
 pub mod piece;
 pub mod pieces;
+pub mod polyglot;
 pub mod square;
+pub mod validation;
 pub mod zobrist;
 
 use bitboard::BitboardUtils;
 use color::ColorUtils;
+use file_rank::{FileUtils, RankUtils};
 use piece::PieceUtils;
+use square::SquareUtils;
 use std::sync::Arc;
 use zobrist::{HashTable, ZobristHash};
 
@@ -45,6 +50,17 @@ impl Default for Board {
 }
 
 impl Board {
+	/// The seed of the Zobrist keys the board is hashed with.
+	pub fn zobrist_seed(&self) -> u64 {
+		self.hash_table.seed()
+	}
+
+	/// Switches to the Zobrist keys generated from `seed` and rehashes the position.
+	pub fn set_zobrist_seed(&mut self, seed: u64) {
+		self.hash_table = HashTable::shared(seed);
+		self.hash = self.init_hash();
+	}
+
 	#[inline(always)]
 	pub fn add_piece(&mut self, piece: Piece, color: Color, square: Square) {
 		self.piece_list[square] = piece;
@@ -81,18 +97,53 @@ impl Board {
 		self.hash ^= self.hash_table.castle(self.castle_rights);
 	}
 
+	/// Sets the en passant square, after the side to move has been switched to the side that
+	/// may capture.
 	#[inline(always)]
 	pub fn set_en_passant(&mut self, square: Square) {
-		self.hash ^= self.hash_table.en_passant(self.en_passant);
+		self.hash ^= self.en_passant_key();
 		self.en_passant = Some(square);
-		self.hash ^= self.hash_table.en_passant(self.en_passant);
+		self.hash ^= self.en_passant_key();
 	}
 
 	#[inline(always)]
 	pub fn clear_en_passant(&mut self) {
-		self.hash ^= self.hash_table.en_passant(self.en_passant);
+		self.hash ^= self.en_passant_key();
 		self.en_passant = None;
-		self.hash ^= self.hash_table.en_passant(self.en_passant);
+		self.hash ^= self.en_passant_key();
+	}
+
+	/// The key of the en passant square, which only counts when a pawn can capture, so that
+	/// positions equal under the repetition rules share a hash.
+	#[inline(always)]
+	pub(crate) fn en_passant_key(&self) -> ZobristHash {
+		let en_passant = self
+			.en_passant
+			.filter(|&square| self.en_passant_capturable(square));
+
+		self.hash_table.en_passant(en_passant)
+	}
+
+	/// Whether a pawn of the side to move stands next to the pawn that can be taken en passant.
+	pub(crate) fn en_passant_capturable(&self, en_passant: Square) -> bool {
+		let pawn = match (self.color, SquareUtils::location(en_passant).1) {
+			(ColorUtils::WHITE, RankUtils::R6) => en_passant - 8,
+			(ColorUtils::BLACK, RankUtils::R3) => en_passant + 8,
+			_ => return false,
+		};
+
+		let (file, _) = SquareUtils::location(pawn);
+		let mut neighbours = BitboardUtils::EMPTY;
+
+		if file > FileUtils::A {
+			neighbours |= BitboardUtils::SQUARES[pawn - 1];
+		}
+
+		if file < FileUtils::H {
+			neighbours |= BitboardUtils::SQUARES[pawn + 1];
+		}
+
+		neighbours & self.pieces[self.color][PieceUtils::PAWN] != 0
 	}
 }
 
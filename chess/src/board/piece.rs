@@ -31,14 +31,21 @@ impl PieceUtils {
 
 impl PieceUtils {
 	pub fn parse(value: char) -> Piece {
+		match Self::try_parse(value) {
+			Some(piece) => piece,
+			None => panic!("Invalid piece: {}", value),
+		}
+	}
+
+	pub fn try_parse(value: char) -> Option<Piece> {
 		match value {
-			'P' | 'p' => Self::PAWN,
-			'N' | 'n' => Self::KNIGHT,
-			'B' | 'b' => Self::BISHOP,
-			'R' | 'r' => Self::ROOK,
-			'Q' | 'q' => Self::QUEEN,
-			'K' | 'k' => Self::KING,
-			_ => panic!("Invalid piece: {}", value),
+			'P' | 'p' => Some(Self::PAWN),
+			'N' | 'n' => Some(Self::KNIGHT),
+			'B' | 'b' => Some(Self::BISHOP),
+			'R' | 'r' => Some(Self::ROOK),
+			'Q' | 'q' => Some(Self::QUEEN),
+			'K' | 'k' => Some(Self::KING),
+			_ => None,
 		}
 	}
 
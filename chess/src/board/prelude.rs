@@ -1,7 +1,9 @@
 pub use super::bitboard::Bitboard;
 pub use super::castle_right::CastleRight;
 pub use super::color::Color;
+pub use super::fen::{FenError, FenField};
 pub use super::file_rank::{File, Rank};
 pub use super::piece::Piece;
 pub use super::pieces::{BitboardPieces, PieceList};
 pub use super::square::Square;
+pub use super::validation::ValidationIssue;
@@ -85,16 +85,23 @@ impl SquareUtils {
 	}
 
 	pub fn parse(value: &str) -> Square {
-		let chars = value.chars().collect::<Vec<char>>();
+		match Self::try_parse(value) {
+			Some(square) => square,
+			None => panic!("Invalid square: {}", value),
+		}
+	}
+
+	pub fn try_parse(value: &str) -> Option<Square> {
+		let mut chars = value.chars();
 
-		match (chars.first(), chars.get(1)) {
-			(Some(file), Some(rank)) => {
-				let file = FileUtils::from_char(*file);
-				let rank = FileUtils::from_char(*rank);
+		match (chars.next(), chars.next(), chars.next()) {
+			(Some(file), Some(rank), None) => {
+				let file = FileUtils::try_from_char(file)?;
+				let rank = RankUtils::try_from_char(rank)?;
 
-				rank * 8 + file
+				Some(Self::from_location(file, rank))
 			}
-			_ => panic!("Invalid square: {}", value),
+			_ => None,
 		}
 	}
 
@@ -102,6 +109,14 @@ impl SquareUtils {
 		(square % 8, square / 8)
 	}
 
+	/// The number of king moves between two squares.
+	pub fn distance(a: Square, b: Square) -> usize {
+		let (file_a, rank_a) = Self::location(a);
+		let (file_b, rank_b) = Self::location(b);
+
+		file_a.abs_diff(file_b).max(rank_a.abs_diff(rank_b))
+	}
+
 	pub fn to_string(square: Square) -> String {
 		let (file, rank) = Self::location(square);
 
@@ -0,0 +1,66 @@
+use super::{
+	castle_right::CastleRightUtils, color::ColorUtils, square::SquareUtils, CastleRight, Color,
+	Square,
+};
+use std::{error::Error, fmt};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+	/// A side does not have exactly one king.
+	KingCount { color: Color, count: u32 },
+	/// A side has more than sixteen pieces.
+	TooManyPieces { color: Color, count: u32 },
+	/// A side has more than eight pawns.
+	TooManyPawns { color: Color, count: u32 },
+	/// A pawn stands on the first or eighth rank.
+	PawnOnBackRank(Square),
+	/// A castling right is set although its king or rook left the starting square.
+	InvalidCastleRight(CastleRight),
+	/// The en passant square could not have been left by a double pawn push.
+	InvalidEnPassant(Square),
+	/// The side that is not to move is in check.
+	OpponentInCheck,
+}
+
+impl fmt::Display for ValidationIssue {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::KingCount { color, count } => {
+				write!(f, "{} has {count} kings", color_name(*color))
+			}
+			Self::TooManyPieces { color, count } => {
+				write!(f, "{} has {count} pieces", color_name(*color))
+			}
+			Self::TooManyPawns { color, count } => {
+				write!(f, "{} has {count} pawns", color_name(*color))
+			}
+			Self::PawnOnBackRank(square) => {
+				write!(
+					f,
+					"pawn on back rank at {}",
+					SquareUtils::to_string(*square)
+				)
+			}
+			Self::InvalidCastleRight(castle_right) => write!(
+				f,
+				"castling right {} without king or rook in place",
+				CastleRightUtils::to_string(*castle_right)
+			),
+			Self::InvalidEnPassant(square) => write!(
+				f,
+				"en passant square {} without a double-pushed pawn",
+				SquareUtils::to_string(*square)
+			),
+			Self::OpponentInCheck => write!(f, "side not to move is in check"),
+		}
+	}
+}
+
+impl Error for ValidationIssue {}
+
+fn color_name(color: Color) -> &'static str {
+	match color {
+		ColorUtils::WHITE => "white",
+		_ => "black",
+	}
+}
@@ -3,7 +3,7 @@ use super::{
 	CastleRight, Color, Piece, Square,
 };
 
-use rand::Rng;
+use std::sync::{Arc, OnceLock};
 
 type PieceTable = [[[ZobristHash; SquareUtils::SIZE]; PieceUtils::SIZE]; ColorUtils::SIZE];
 type ColorTable = [ZobristHash; ColorUtils::SIZE];
@@ -12,8 +12,18 @@ type EnPassantTable = [ZobristHash; SquareUtils::SIZE + 1];
 
 pub(crate) type ZobristHash = u64;
 
+pub struct ZobristUtils;
+
+impl ZobristUtils {
+	/// The seed of the keys every board uses unless another one is given.
+	pub const DEFAULT_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+}
+
+/// The random keys of the Zobrist hash, generated from a seed so hashes are the same on every
+/// run and every machine.
 #[derive(Debug)]
 pub(super) struct HashTable {
+	seed: u64,
 	pieces: PieceTable,
 	colors: ColorTable,
 	castles: CastleTable,
@@ -22,9 +32,16 @@ pub(super) struct HashTable {
 
 impl Default for HashTable {
 	fn default() -> Self {
-		let mut random = rand::thread_rng();
+		Self::new(ZobristUtils::DEFAULT_SEED)
+	}
+}
+
+impl HashTable {
+	pub(super) fn new(seed: u64) -> Self {
+		let mut random = SplitMix64(seed);
 
 		let mut hash_table = Self {
+			seed,
 			pieces: [[[0; SquareUtils::SIZE]; PieceUtils::SIZE]; ColorUtils::SIZE],
 			colors: [0; ColorUtils::SIZE],
 			castles: [0; CastleRightUtils::SIZE],
@@ -33,27 +50,57 @@ impl Default for HashTable {
 
 		hash_table.pieces.iter_mut().for_each(|color| {
 			color.iter_mut().for_each(|piece| {
-				piece.iter_mut().for_each(|square| *square = random.gen());
+				piece.iter_mut().for_each(|square| *square = random.next());
 			})
 		});
 
 		hash_table
 			.colors
 			.iter_mut()
-			.for_each(|color| *color = random.gen());
+			.for_each(|color| *color = random.next());
 
 		hash_table
 			.castles
 			.iter_mut()
-			.for_each(|castle| *castle = random.gen());
+			.for_each(|castle| *castle = random.next());
 
 		hash_table
 			.en_passant
 			.iter_mut()
-			.for_each(|en_passant| *en_passant = random.gen());
+			.for_each(|en_passant| *en_passant = random.next());
 
 		hash_table
 	}
+
+	/// The table for `seed`, shared by all boards when it is the default seed.
+	pub(super) fn shared(seed: u64) -> Arc<Self> {
+		static DEFAULT: OnceLock<Arc<HashTable>> = OnceLock::new();
+
+		match seed {
+			ZobristUtils::DEFAULT_SEED => DEFAULT.get_or_init(Arc::default).clone(),
+			seed => Arc::new(Self::new(seed)),
+		}
+	}
+
+	pub(super) fn seed(&self) -> u64 {
+		self.seed
+	}
+}
+
+/// The SplitMix64 generator, which is fully specified so the keys never change with a
+/// dependency update.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+	fn next(&mut self) -> u64 {
+		self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+		let mut z = self.0;
+		z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+		z ^ (z >> 31)
+	}
 }
 
 impl HashTable {
@@ -69,7 +116,7 @@ impl HashTable {
 
 	#[inline(always)]
 	pub(super) fn castle(&self, castle: CastleRight) -> ZobristHash {
-		self.castles[castle.trailing_zeros() as usize]
+		self.castles[castle as usize]
 	}
 
 	#[inline(always)]
@@ -80,3 +127,54 @@ impl HashTable {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Chess;
+	use std::collections::HashSet;
+
+	#[test]
+	fn castle_keys_are_distinct() {
+		let hash_table = HashTable::default();
+		let keys = (0..CastleRightUtils::SIZE as CastleRight)
+			.map(|castle| hash_table.castle(castle))
+			.collect::<HashSet<_>>();
+
+		assert_eq!(keys.len(), CastleRightUtils::SIZE);
+	}
+
+	#[test]
+	fn incremental_hash_matches_castle_right_changes() {
+		let mut chess =
+			Chess::try_from("r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1").unwrap();
+
+		for text in [
+			"a1b1", "h8g8", "h1g1", "a8b8", "e1d1", "e8f8", "b1a1", "g8h8",
+		] {
+			let m = chess.parse_uci_move(text).unwrap();
+			chess.make_move(m);
+
+			assert_eq!(chess.board.hash, chess.board.init_hash(), "after {text}");
+		}
+
+		for _ in 0..8 {
+			chess.undo_move();
+
+			assert_eq!(chess.board.hash, chess.board.init_hash());
+		}
+	}
+
+	#[test]
+	fn castle_rights_change_the_hash() {
+		let hashes = ["KQkq", "Kk", "Qq", "K", "-"]
+			.map(|castle| {
+				let fen = format!("r3k2r/8/8/8/8/8/8/R3K2R w {castle} - 0 1");
+				Chess::try_from(fen.as_str()).unwrap().board.hash
+			})
+			.into_iter()
+			.collect::<HashSet<_>>();
+
+		assert_eq!(hashes.len(), 5);
+	}
+}
@@ -0,0 +1,224 @@
+use super::ENTRY_SIZE;
+use crate::{
+	board::{color::ColorUtils, file_rank::FileUtils, piece::PieceUtils, square::SquareUtils},
+	move_gen::Move,
+	pgn::{Game, GameResult},
+};
+use std::{
+	collections::BTreeMap,
+	io::{self, Write},
+};
+
+/// The results of the games in which a move was played, from the side that played it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MoveStats {
+	pub wins: u32,
+	pub draws: u32,
+	pub losses: u32,
+	/// Games without a known result.
+	pub unknown: u32,
+}
+
+impl MoveStats {
+	pub fn games(&self) -> u32 {
+		self.wins + self.draws + self.losses + self.unknown
+	}
+
+	/// Two points for a win and one for a draw, as Polyglot weighs its moves.
+	pub fn score(&self) -> u32 {
+		2 * self.wins + self.draws
+	}
+}
+
+/// Collects the moves played in games into a Polyglot book.
+#[derive(Debug, Clone)]
+pub struct BookBuilder {
+	max_ply: usize,
+	min_games: u32,
+	positions: BTreeMap<u64, BTreeMap<u16, MoveStats>>,
+}
+
+impl BookBuilder {
+	/// A builder that records the first `max_ply` moves of each game, and keeps moves played
+	/// in at least `min_games` games.
+	pub fn new(max_ply: usize, min_games: u32) -> Self {
+		Self {
+			max_ply,
+			min_games,
+			positions: BTreeMap::new(),
+		}
+	}
+
+	/// The number of positions recorded so far.
+	pub fn positions(&self) -> usize {
+		self.positions.len()
+	}
+
+	/// Replays the main line of a game, recording its moves until one cannot be played.
+	pub fn add_game(&mut self, game: &Game) {
+		let mut chess = game.start.clone();
+
+		for pgn_move in game.moves.iter().take(self.max_ply) {
+			let key = chess.board.polyglot_key();
+			let color = chess.board.color;
+
+			if !chess.play_move(pgn_move.m) {
+				break;
+			}
+
+			let stats = self
+				.positions
+				.entry(key)
+				.or_default()
+				.entry(encode(pgn_move.m))
+				.or_default();
+
+			match (game.result, color) {
+				(GameResult::Draw, _) => stats.draws += 1,
+				(GameResult::Unknown, _) => stats.unknown += 1,
+				(GameResult::WhiteWins, ColorUtils::WHITE)
+				| (GameResult::BlackWins, ColorUtils::BLACK) => stats.wins += 1,
+				_ => stats.losses += 1,
+			}
+		}
+	}
+
+	/// Writes the book, sorted by key and then by descending weight, returning the number of
+	/// entries. Weights are scaled to fit 16 bits, and moves that score nothing are left out.
+	pub fn write(&self, mut writer: impl Write) -> io::Result<usize> {
+		let mut written = 0;
+
+		for (key, moves) in &self.positions {
+			let mut moves = moves
+				.iter()
+				.filter(|(_, stats)| stats.games() >= self.min_games && stats.score() > 0)
+				.map(|(m, stats)| (*m, stats.score()))
+				.collect::<Vec<_>>();
+
+			let max_score = moves.iter().map(|(_, score)| *score).max().unwrap_or(0);
+			let scale = |score: u32| match max_score > u16::MAX as u32 {
+				true => (score as u64 * u16::MAX as u64 / max_score as u64).max(1) as u16,
+				false => score as u16,
+			};
+
+			moves.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+
+			for (m, score) in moves {
+				let mut entry = [0; ENTRY_SIZE];
+
+				entry[..8].copy_from_slice(&key.to_be_bytes());
+				entry[8..10].copy_from_slice(&m.to_be_bytes());
+				entry[10..12].copy_from_slice(&scale(score).to_be_bytes());
+
+				writer.write_all(&entry)?;
+				written += 1;
+			}
+		}
+
+		Ok(written)
+	}
+}
+
+/// Encodes a move the way Polyglot does, with castling as the king capturing its own rook.
+fn encode(m: Move) -> u16 {
+	let (from_file, from_rank) = SquareUtils::location(m.from());
+	let (mut to_file, to_rank) = SquareUtils::location(m.to());
+
+	if m.castling() {
+		to_file = match to_file {
+			FileUtils::G => FileUtils::H,
+			_ => FileUtils::A,
+		};
+	}
+
+	let promoted = PieceUtils::PROMOTIONS
+		.iter()
+		.position(|piece| *piece == m.promoted())
+		.map_or(0, |index| index + 1);
+
+	(to_file | to_rank << 3 | from_file << 6 | from_rank << 9 | promoted << 12) as u16
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{book::Book, pgn::PgnReader, Chess};
+	use std::fs;
+
+	const GAMES: &str = r#"1. e4 e5 2. Nf3 1-0
+
+1. e4 c5 0-1
+
+1. d4 d5 1/2-1/2
+
+1. e4 e5 *
+
+[SetUp "1"]
+[FEN "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"]
+
+1. O-O O-O-O 1-0
+"#;
+
+	fn build(min_games: u32) -> (BookBuilder, Book) {
+		let mut builder = BookBuilder::new(2, min_games);
+
+		for game in PgnReader::new(GAMES.as_bytes()) {
+			builder.add_game(&game.unwrap());
+		}
+
+		let mut bytes = Vec::new();
+		let written = builder.write(&mut bytes).unwrap();
+		assert_eq!(bytes.len(), written * ENTRY_SIZE);
+
+		let path =
+			std::env::temp_dir().join(format!("build-{min_games}-{}.bin", std::process::id()));
+		fs::write(&path, bytes).unwrap();
+		let book = Book::open(&path).unwrap();
+		fs::remove_file(&path).unwrap();
+
+		(builder, book)
+	}
+
+	fn moves(book: &Book, fen: &str, played: &str) -> Vec<(String, u16)> {
+		let mut chess = Chess::try_from(fen).unwrap();
+
+		for text in played.split_whitespace() {
+			let m = chess.parse_uci_move(text).unwrap();
+			chess.make_move(m);
+		}
+
+		book.entries(&chess)
+			.iter()
+			.map(|entry| (entry.m.to_string(), entry.weight))
+			.collect()
+	}
+
+	#[test]
+	fn build_and_read() {
+		let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+		let castling = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+		let (builder, book) = build(1);
+
+		assert_eq!(builder.positions(), 5);
+
+		// e4 won one game, lost one and has an unknown result, d4 drew its only game.
+		assert_eq!(
+			moves(&book, start, ""),
+			[("e2e4".to_string(), 2), ("d2d4".to_string(), 1)]
+		);
+		// e5 scored nothing and is left out, moves past the second ply are not recorded.
+		assert_eq!(moves(&book, start, "e2e4"), [("c7c5".to_string(), 2)]);
+		assert_eq!(moves(&book, start, "e2e4 e7e5"), []);
+		assert_eq!(moves(&book, castling, ""), [("e1g1".to_string(), 2)]);
+		assert_eq!(moves(&book, castling, "e1g1"), []);
+	}
+
+	#[test]
+	fn leave_out_rare_moves() {
+		let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+		let (_, book) = build(2);
+
+		assert_eq!(moves(&book, start, ""), [("e2e4".to_string(), 2)]);
+		assert_eq!(book.len(), 1);
+	}
+}
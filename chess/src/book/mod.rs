@@ -0,0 +1,246 @@
+mod builder;
+
+pub use builder::{BookBuilder, MoveStats};
+
+use crate::{
+	board::{file_rank::FileUtils, piece::PieceUtils, square::SquareUtils},
+	move_gen::Move,
+	Chess,
+};
+use memmap2::Mmap;
+use rand::Rng;
+use std::{fs::File, io, path::Path};
+
+const ENTRY_SIZE: usize = 16;
+
+/// How a move is chosen among the book moves of a position.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+	/// Each move is chosen with a probability proportional to its weight.
+	#[default]
+	Weighted,
+	Uniform,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BookEntry {
+	pub m: Move,
+	pub weight: u16,
+	pub learn: u32,
+}
+
+/// A Polyglot opening book, memory-mapped from a `.bin` file. Entries are 16 big-endian bytes
+/// sorted by the Polyglot key of their position: the key, the move, its weight and a learn
+/// value.
+pub struct Book {
+	map: Mmap,
+}
+
+impl Book {
+	pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+		let file = File::open(path)?;
+
+		// Safety: the book is only read, and is expected not to change while it is open.
+		let map = unsafe { Mmap::map(&file)? };
+
+		if map.len() % ENTRY_SIZE != 0 {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"book size is not a multiple of the entry size",
+			));
+		}
+
+		Ok(Self { map })
+	}
+
+	/// The number of entries in the book.
+	pub fn len(&self) -> usize {
+		self.raw_entries().len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// The entries for the current position, in book order. Moves that are not legal in the
+	/// position are skipped.
+	pub fn entries(&self, chess: &Chess) -> Vec<BookEntry> {
+		let key = chess.board.polyglot_key();
+		let entries = self.raw_entries();
+		let first = entries.partition_point(|entry| entry_key(entry) < key);
+
+		entries[first..]
+			.iter()
+			.take_while(|entry| entry_key(entry) == key)
+			.filter_map(|entry| {
+				Some(BookEntry {
+					m: decode(chess, u16::from_be_bytes([entry[8], entry[9]]))?,
+					weight: u16::from_be_bytes([entry[10], entry[11]]),
+					learn: u32::from_be_bytes([entry[12], entry[13], entry[14], entry[15]]),
+				})
+			})
+			.collect()
+	}
+
+	/// Picks a book move for the current position. Weighted selection falls back to a uniform
+	/// choice when every move has a weight of zero.
+	pub fn pick(&self, chess: &Chess, selection: Selection) -> Option<Move> {
+		let entries = self.entries(chess);
+		let total = entries.iter().map(|entry| entry.weight as u32).sum::<u32>();
+		let mut random = rand::thread_rng();
+
+		if entries.is_empty() {
+			return None;
+		}
+
+		match selection {
+			Selection::Weighted if total > 0 => {
+				let mut target = random.gen_range(0..total);
+
+				entries
+					.iter()
+					.find_map(|entry| match target.checked_sub(entry.weight as u32) {
+						Some(rest) => {
+							target = rest;
+							None
+						}
+						None => Some(entry.m),
+					})
+			}
+			_ => Some(entries[random.gen_range(0..entries.len())].m),
+		}
+	}
+
+	fn raw_entries(&self) -> &[[u8; ENTRY_SIZE]] {
+		self.map.as_chunks().0
+	}
+}
+
+#[inline(always)]
+fn entry_key(entry: &[u8; ENTRY_SIZE]) -> u64 {
+	u64::from_be_bytes(entry[..8].try_into().unwrap())
+}
+
+/// Decodes a Polyglot move, which stores castling as the king capturing its own rook.
+fn decode(chess: &Chess, data: u16) -> Option<Move> {
+	let square = |shift: u16| {
+		let file = (data >> shift) as usize & 0x7;
+		let rank = (data >> (shift + 3)) as usize & 0x7;
+
+		SquareUtils::from_location(file, rank)
+	};
+
+	let from = square(6);
+	let mut to = square(0);
+
+	let promoted = match (data >> 12) as usize & 0x7 {
+		0 => PieceUtils::NONE,
+		piece => *PieceUtils::PROMOTIONS.get(piece - 1)?,
+	};
+
+	let (from_file, from_rank) = SquareUtils::location(from);
+	let (to_file, to_rank) = SquareUtils::location(to);
+
+	if chess.board.piece_list[from] == PieceUtils::KING
+		&& from_file == FileUtils::E
+		&& from_rank == to_rank
+	{
+		match to_file {
+			FileUtils::H => to = SquareUtils::from_location(FileUtils::G, to_rank),
+			FileUtils::A => to = SquareUtils::from_location(FileUtils::C, to_rank),
+			_ => {}
+		}
+	}
+
+	chess
+		.generate_legal_moves()
+		.iter()
+		.find(|m| m.from() == from && m.to() == to && m.promoted() == promoted)
+		.copied()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{fs, path::PathBuf};
+
+	/// A Polyglot entry moving between two squares, with castling given as the king capturing
+	/// its own rook.
+	fn entry(chess: &Chess, from: &str, to: &str, weight: u16) -> [u8; ENTRY_SIZE] {
+		let (from_file, from_rank) = SquareUtils::location(SquareUtils::try_parse(from).unwrap());
+		let (to_file, to_rank) = SquareUtils::location(SquareUtils::try_parse(to).unwrap());
+		let data = (to_file | to_rank << 3 | from_file << 6 | from_rank << 9) as u16;
+
+		let mut entry = [0; ENTRY_SIZE];
+
+		entry[..8].copy_from_slice(&chess.board.polyglot_key().to_be_bytes());
+		entry[8..10].copy_from_slice(&data.to_be_bytes());
+		entry[10..12].copy_from_slice(&weight.to_be_bytes());
+		entry
+	}
+
+	fn write_book(name: &str, mut entries: Vec<[u8; ENTRY_SIZE]>) -> PathBuf {
+		let path = std::env::temp_dir().join(format!("{name}-{}.bin", std::process::id()));
+
+		entries.sort_by_key(entry_key);
+		fs::write(&path, entries.concat()).unwrap();
+		path
+	}
+
+	#[test]
+	fn read_entries() {
+		let start = Chess::default();
+		let castling = Chess::try_from("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+		let path = write_book(
+			"read-entries",
+			vec![
+				entry(&start, "e2", "e4", 3),
+				entry(&start, "d2", "d4", 2),
+				entry(&start, "e2", "e5", 9),
+				entry(&castling, "e1", "h1", 1),
+				entry(&castling, "e1", "a1", 0),
+			],
+		);
+		let book = Book::open(&path).unwrap();
+		fs::remove_file(&path).unwrap();
+
+		assert_eq!(book.len(), 5);
+
+		let moves = |chess: &Chess| {
+			book.entries(chess)
+				.iter()
+				.map(|entry| (entry.m, entry.weight))
+				.collect::<Vec<_>>()
+		};
+		let uci = |chess: &Chess, text| chess.parse_uci_move(text).unwrap();
+
+		// The illegal e2e5 is skipped.
+		assert_eq!(
+			moves(&start),
+			[(uci(&start, "e2e4"), 3), (uci(&start, "d2d4"), 2)]
+		);
+		assert_eq!(
+			moves(&castling),
+			[(uci(&castling, "e1g1"), 1), (uci(&castling, "e1c1"), 0)]
+		);
+		assert_eq!(
+			book.pick(&castling, Selection::Weighted),
+			Some(uci(&castling, "e1g1"))
+		);
+		assert!(book
+			.entries(&Chess::try_from("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap())
+			.is_empty());
+	}
+
+	#[test]
+	fn reject_truncated_books() {
+		let path = std::env::temp_dir().join(format!("truncated-{}.bin", std::process::id()));
+
+		fs::write(&path, [0; ENTRY_SIZE + 1]).unwrap();
+		let error = Book::open(&path).err().unwrap();
+		fs::remove_file(&path).unwrap();
+
+		assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+	}
+}
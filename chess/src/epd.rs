@@ -0,0 +1,384 @@
+use crate::board::{Board, FenError};
+use std::{error::Error, fmt, str::FromStr};
+
+/// The opcodes of an EPD operation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Opcode {
+	/// `id`, the name of the record.
+	Id,
+	/// `bm`, the best moves.
+	BestMove,
+	/// `am`, the moves to avoid.
+	AvoidMove,
+	/// `ce`, the centipawn evaluation.
+	CentipawnEvaluation,
+	/// `D1` to `D9`, the perft node count at that depth.
+	Perft(u8),
+	/// `hmvc`, the halfmove clock.
+	HalfmoveClock,
+	/// `fmvn`, the fullmove number.
+	FullmoveNumber,
+	/// `c0` to `c9`, comments.
+	Comment(u8),
+	Other(String),
+}
+
+impl fmt::Display for Opcode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Id => write!(f, "id"),
+			Self::BestMove => write!(f, "bm"),
+			Self::AvoidMove => write!(f, "am"),
+			Self::CentipawnEvaluation => write!(f, "ce"),
+			Self::Perft(depth) => write!(f, "D{depth}"),
+			Self::HalfmoveClock => write!(f, "hmvc"),
+			Self::FullmoveNumber => write!(f, "fmvn"),
+			Self::Comment(index) => write!(f, "c{index}"),
+			Self::Other(opcode) => write!(f, "{opcode}"),
+		}
+	}
+}
+
+impl From<&str> for Opcode {
+	fn from(opcode: &str) -> Self {
+		let digit = |prefix| {
+			opcode
+				.strip_prefix(prefix)
+				.filter(|digit: &&str| digit.len() == 1)
+				.and_then(|digit| digit.parse::<u8>().ok())
+		};
+
+		match opcode {
+			"id" => Self::Id,
+			"bm" => Self::BestMove,
+			"am" => Self::AvoidMove,
+			"ce" => Self::CentipawnEvaluation,
+			"hmvc" => Self::HalfmoveClock,
+			"fmvn" => Self::FullmoveNumber,
+			_ => match (digit("D"), digit("c")) {
+				(Some(depth), _) if depth > 0 => Self::Perft(depth),
+				(_, Some(index)) => Self::Comment(index),
+				_ => Self::Other(opcode.to_string()),
+			},
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operand {
+	/// Moves in Standard Algebraic Notation, resolved against the position with
+	/// `Chess::parse_san`.
+	Moves(Vec<String>),
+	String(String),
+	Integer(i64),
+	/// The raw operands of an opcode this module does not know.
+	Other(Vec<String>),
+}
+
+impl fmt::Display for Operand {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Moves(moves) => write!(f, "{}", moves.join(" ")),
+			Self::String(text) => write!(f, "\"{text}\""),
+			Self::Integer(value) => write!(f, "{value}"),
+			Self::Other(operands) => {
+				let operands = operands
+					.iter()
+					.map(|operand| {
+						match operand.is_empty()
+							|| operand.contains(|ch: char| ch.is_whitespace() || ch == ';')
+						{
+							true => format!("\"{operand}\""),
+							false => operand.clone(),
+						}
+					})
+					.collect::<Vec<_>>();
+
+				write!(f, "{}", operands.join(" "))
+			}
+		}
+	}
+}
+
+/// An Extended Position Description record: a position without move counters, followed by
+/// operations such as `bm Nf3; id "WAC.001";`.
+#[derive(Debug, Clone)]
+pub struct Epd {
+	pub board: Board,
+	/// The operations in the order they are written, each opcode at most once.
+	pub operations: Vec<(Opcode, Operand)>,
+}
+
+impl Epd {
+	pub fn operation(&self, opcode: &Opcode) -> Option<&Operand> {
+		self.operations
+			.iter()
+			.find(|(other, _)| other == opcode)
+			.map(|(_, operand)| operand)
+	}
+
+	pub fn id(&self) -> Option<&str> {
+		match self.operation(&Opcode::Id) {
+			Some(Operand::String(id)) => Some(id),
+			_ => None,
+		}
+	}
+
+	/// The expected perft node counts by depth, from the `D1` to `D9` opcodes.
+	pub fn perft_counts(&self) -> Vec<(u8, u64)> {
+		self.operations
+			.iter()
+			.filter_map(|(opcode, operand)| match (opcode, operand) {
+				(Opcode::Perft(depth), Operand::Integer(nodes)) => Some((*depth, *nodes as u64)),
+				_ => None,
+			})
+			.collect()
+	}
+}
+
+impl FromStr for Epd {
+	type Err = EpdError;
+
+	/// Parses an EPD record. Full FENs with move counters are accepted, and the `hmvc` and
+	/// `fmvn` operations set the counters of the board.
+	fn from_str(line: &str) -> Result<Self, Self::Err> {
+		let mut rest = line.trim();
+		let mut fields = Vec::new();
+
+		for index in 0..6 {
+			rest = rest.trim_start();
+
+			let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+			let field = &rest[..end];
+
+			if index >= 4 && (field.is_empty() || !field.chars().all(|ch| ch.is_ascii_digit())) {
+				break;
+			}
+
+			fields.push(field);
+			rest = &rest[end..];
+		}
+
+		let mut board = Board::from_fen(&fields.join(" ")).map_err(EpdError::InvalidFen)?;
+		let mut operations = Vec::<(Opcode, Operand)>::new();
+
+		for (opcode, operands) in split_operations(rest)? {
+			let opcode = Opcode::from(opcode.as_str());
+
+			if operations.iter().any(|(other, _)| *other == opcode) {
+				return Err(EpdError::DuplicateOpcode(opcode));
+			}
+
+			let operand = parse_operand(&opcode, operands)?;
+
+			if let Operand::Integer(value) = operand {
+				let invalid = || EpdError::InvalidOperand {
+					opcode: opcode.clone(),
+					operand: value.to_string(),
+				};
+
+				match opcode {
+					Opcode::HalfmoveClock => {
+						board.halfmove_clock = value.try_into().map_err(|_| invalid())?
+					}
+					Opcode::FullmoveNumber => {
+						board.fullmove_number = value.try_into().map_err(|_| invalid())?
+					}
+					_ => {}
+				}
+			}
+
+			operations.push((opcode, operand));
+		}
+
+		Ok(Self { board, operations })
+	}
+}
+
+impl fmt::Display for Epd {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let fen = self.board.to_fen();
+		let fields = fen.split_whitespace().take(4).collect::<Vec<_>>();
+
+		write!(f, "{}", fields.join(" "))?;
+
+		for (opcode, operand) in &self.operations {
+			match operand {
+				Operand::Other(operands) if operands.is_empty() => write!(f, " {opcode};")?,
+				_ => write!(f, " {opcode} {operand};")?,
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Splits the operations of a record into opcodes and their operands. Empty operations are
+/// skipped and the final `;` may be omitted.
+fn split_operations(text: &str) -> Result<Vec<(String, Vec<String>)>, EpdError> {
+	let mut operations = Vec::new();
+	let mut words = Vec::new();
+	let mut word = String::new();
+	let mut chars = text.chars();
+
+	loop {
+		let ch = chars.next();
+
+		match ch {
+			Some('"') => {
+				let mut string = String::new();
+
+				loop {
+					match chars.next() {
+						Some('"') => break,
+						Some(ch) => string.push(ch),
+						None => return Err(EpdError::UnterminatedString),
+					}
+				}
+
+				finish_word(&mut words, &mut word);
+				words.push(string);
+			}
+			Some(ch) if !ch.is_whitespace() && ch != ';' => word.push(ch),
+			_ => finish_word(&mut words, &mut word),
+		}
+
+		if matches!(ch, None | Some(';')) && !words.is_empty() {
+			let opcode = words.remove(0);
+			operations.push((opcode, std::mem::take(&mut words)));
+		}
+
+		if ch.is_none() {
+			return Ok(operations);
+		}
+	}
+}
+
+fn finish_word(words: &mut Vec<String>, word: &mut String) {
+	if !word.is_empty() {
+		words.push(std::mem::take(word));
+	}
+}
+
+fn parse_operand(opcode: &Opcode, operands: Vec<String>) -> Result<Operand, EpdError> {
+	if operands.is_empty() && !matches!(opcode, Opcode::Other(_)) {
+		return Err(EpdError::MissingOperand(opcode.clone()));
+	}
+
+	match opcode {
+		Opcode::BestMove | Opcode::AvoidMove => Ok(Operand::Moves(operands)),
+		Opcode::Id | Opcode::Comment(_) => Ok(Operand::String(operands.join(" "))),
+		Opcode::CentipawnEvaluation
+		| Opcode::Perft(_)
+		| Opcode::HalfmoveClock
+		| Opcode::FullmoveNumber => {
+			let operand = operands.join(" ");
+
+			match operand.parse() {
+				Ok(value) => Ok(Operand::Integer(value)),
+				Err(_) => Err(EpdError::InvalidOperand {
+					opcode: opcode.clone(),
+					operand,
+				}),
+			}
+		}
+		Opcode::Other(_) => Ok(Operand::Other(operands)),
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EpdError {
+	/// The position fields do not form a valid FEN.
+	InvalidFen(FenError),
+	UnterminatedString,
+	/// An opcode that requires an operand has none.
+	MissingOperand(Opcode),
+	/// The operand does not have the type its opcode expects.
+	InvalidOperand {
+		opcode: Opcode,
+		operand: String,
+	},
+	/// An opcode appears more than once in a record.
+	DuplicateOpcode(Opcode),
+}
+
+impl fmt::Display for EpdError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidFen(error) => write!(f, "invalid position: {error}"),
+			Self::UnterminatedString => write!(f, "unterminated string operand"),
+			Self::MissingOperand(opcode) => write!(f, "missing operand for '{opcode}'"),
+			Self::InvalidOperand { opcode, operand } => {
+				write!(f, "invalid operand '{operand}' for '{opcode}'")
+			}
+			Self::DuplicateOpcode(opcode) => write!(f, "duplicate opcode '{opcode}'"),
+		}
+	}
+}
+
+impl Error for EpdError {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn round_trip() {
+		let lines = [
+			r#"r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - bm Qxf7#; id "mate in one"; c0 "Scholar's mate";"#,
+			"8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - D2 191; D1 14; xyz; pv Kb6 \"two words\";",
+			r#"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - ce -12; am e4 d4; hmvc 0; fmvn 1;"#,
+		];
+
+		for line in lines {
+			let epd = line.parse::<Epd>().unwrap();
+
+			assert_eq!(epd.to_string(), line);
+		}
+	}
+
+	#[test]
+	fn operations() {
+		let epd = "4k3/8/8/8/8/8/8/4K3 w - - id \"kings\"; D2 9; D1 5; hmvc 12; fmvn 40"
+			.parse::<Epd>()
+			.unwrap();
+
+		assert_eq!(epd.id(), Some("kings"));
+		assert_eq!(epd.perft_counts(), [(2, 9), (1, 5)]);
+		assert_eq!(epd.board.halfmove_clock, 12);
+		assert_eq!(epd.board.fullmove_number, 40);
+		assert_eq!(
+			epd.operation(&Opcode::HalfmoveClock),
+			Some(&Operand::Integer(12))
+		);
+	}
+
+	#[test]
+	fn invalid_records() {
+		let cases = [
+			(
+				"4k3/8/8/8/8/8/8/4K3 w - - D1 5; D1 6;",
+				EpdError::DuplicateOpcode(Opcode::Perft(1)),
+			),
+			(
+				"4k3/8/8/8/8/8/8/4K3 w - - bm;",
+				EpdError::MissingOperand(Opcode::BestMove),
+			),
+			(
+				"4k3/8/8/8/8/8/8/4K3 w - - D1 many;",
+				EpdError::InvalidOperand {
+					opcode: Opcode::Perft(1),
+					operand: "many".to_string(),
+				},
+			),
+			(
+				"4k3/8/8/8/8/8/8/4K3 w - - id \"open;",
+				EpdError::UnterminatedString,
+			),
+		];
+
+		for (line, error) in cases {
+			assert_eq!(line.parse::<Epd>().unwrap_err(), error, "{line}");
+		}
+	}
+}
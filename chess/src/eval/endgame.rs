@@ -0,0 +1,290 @@
+use super::{kpk::Kpk, kxk::Kxk};
+use crate::{
+	board::{
+		bitboard::BitboardUtils,
+		color::ColorUtils,
+		file_rank::{FileUtils, RankUtils},
+		piece::PieceUtils,
+		square::SquareUtils,
+		Board, Color, Piece, Square,
+	},
+	move_gen::MoveGen,
+	retrograde::Dtm,
+	search::Score,
+};
+
+/// The pieces of one side apart from its king, as four bits of count per piece type.
+pub type Signature = u32;
+
+pub struct SignatureUtils;
+
+impl SignatureUtils {
+	pub const KING: Signature = 0;
+	pub const PAWN: Signature = 1;
+	pub const KNIGHT: Signature = 1 << 4;
+	pub const BISHOP: Signature = 1 << 8;
+	pub const ROOK: Signature = 1 << 12;
+	pub const QUEEN: Signature = 1 << 16;
+
+	/// The masks of the pawn, rook and queen counts.
+	pub const PAWNS: Signature = 0xF;
+	pub const ROOKS: Signature = 0xF << 12;
+	pub const QUEENS: Signature = 0xF << 16;
+}
+
+impl SignatureUtils {
+	pub fn of(board: &Board, color: Color) -> Signature {
+		PieceUtils::RANGE
+			.take(PieceUtils::KING)
+			.map(|piece| board.pieces[color][piece].count_ones().min(15) << (4 * piece))
+			.sum()
+	}
+}
+
+/// A score the search treats as won, well below any mate score.
+const KNOWN_WIN: Score = 10_000;
+const KBN: Signature = SignatureUtils::KNIGHT + SignatureUtils::BISHOP;
+/// Piece values added to won endings against a lone king, so that promoting and keeping
+/// material still improve the score.
+const PIECE_VALUES: [Score; PieceUtils::KING] = [100, 300, 300, 500, 900];
+/// Added to queen or rook against king wins for each ply the mate is nearer than 64, longer
+/// than the longest mate of either ending. Each move of the winning side then scores better than
+/// the last, and a pawn promotes at once instead of after some waiting moves. The bonus stays
+/// below a pawn, so keeping material still scores higher.
+const MATE_DISTANCE: Score = 1;
+
+/// Scores endings whose outcome is known, from the side to move's point of view.
+pub(super) fn recognize(board: &Board, move_gen: &MoveGen) -> Option<Score> {
+	let signatures = [
+		SignatureUtils::of(board, ColorUtils::WHITE),
+		SignatureUtils::of(board, ColorUtils::BLACK),
+	];
+
+	for strong in [ColorUtils::WHITE, ColorUtils::BLACK] {
+		let weak = strong ^ 1;
+		let ending = Ending {
+			board,
+			move_gen,
+			strong,
+			strong_king: BitboardUtils::lsb(board.pieces[strong][PieceUtils::KING]),
+			weak_king: BitboardUtils::lsb(board.pieces[weak][PieceUtils::KING]),
+		};
+
+		let score = match (signatures[strong], signatures[weak]) {
+			(SignatureUtils::KING, SignatureUtils::KING) => Some(0),
+			(SignatureUtils::KNIGHT | SignatureUtils::BISHOP, SignatureUtils::KING) => Some(0),
+			(SignatureUtils::PAWN, SignatureUtils::KING) => Some(ending.kpk()),
+			(SignatureUtils::QUEEN, SignatureUtils::KING) => Some(ending.kxk(PieceUtils::QUEEN)),
+			(SignatureUtils::ROOK, SignatureUtils::KING) => Some(ending.kxk(PieceUtils::ROOK)),
+			(signature, SignatureUtils::KING)
+				if signature & (SignatureUtils::QUEENS | SignatureUtils::ROOKS) != 0 =>
+			{
+				Some(ending.heavy_piece(signature))
+			}
+			(KBN, SignatureUtils::KING) => Some(ending.kbnk()),
+			(signature, SignatureUtils::KING)
+				if signature & SignatureUtils::PAWNS != 0
+					&& signature & !SignatureUtils::PAWNS != 0 =>
+			{
+				ending.wrong_bishop(signature)
+			}
+			_ => None,
+		};
+
+		if let Some(score) = score {
+			return Some(match board.color == strong {
+				true => score,
+				false => -score,
+			});
+		}
+	}
+
+	None
+}
+
+/// An ending of a side with material against a lone king, scored for the stronger side.
+struct Ending<'a> {
+	board: &'a Board,
+	move_gen: &'a MoveGen,
+	strong: Color,
+	strong_king: Square,
+	weak_king: Square,
+}
+
+impl Ending<'_> {
+	/// Wins score below any ending with a queen or rook, so the pawn promotes.
+	fn kpk(&self) -> Score {
+		let pawn = BitboardUtils::lsb(self.board.pieces[self.strong][PieceUtils::PAWN]);
+		let won = Kpk::get(self.move_gen).probe(
+			self.strong,
+			self.strong_king,
+			pawn,
+			self.weak_king,
+			self.board.color,
+		);
+
+		match won {
+			true => KNOWN_WIN + relative_rank(self.strong, pawn) as Score * 20,
+			false => 0,
+		}
+	}
+
+	/// Scores a queen or rook against a lone king by its distance to mate. Positions where the
+	/// lone king takes the piece or is stalemated are draws.
+	fn kxk(&self, piece: Piece) -> Score {
+		let plies = match (
+			Kxk::get().probe(self.board, piece),
+			self.board.color == self.strong,
+		) {
+			(Dtm::Win(plies), true) | (Dtm::Loss(plies), false) => plies as Score,
+			_ => return 0,
+		};
+
+		KNOWN_WIN + PIECE_VALUES[piece] + MATE_DISTANCE * (64 - plies)
+	}
+
+	/// A queen or rook wins against a lone king whatever else is on the board. Drives the lone
+	/// king to the edge with the stronger king close by.
+	fn heavy_piece(&self, signature: Signature) -> Score {
+		let material = PIECE_VALUES
+			.iter()
+			.enumerate()
+			.map(|(piece, value)| (signature >> (4 * piece) & 0xF) as Score * value)
+			.sum::<Score>();
+
+		KNOWN_WIN + material + 20 * edge_distance(self.weak_king) + self.closeness()
+	}
+
+	/// Drives the lone king to a corner of the bishop's colour, the only ones it can be mated in.
+	fn kbnk(&self) -> Score {
+		let bishops = self.board.pieces[self.strong][PieceUtils::BISHOP];
+
+		let corners = match bishops & BitboardUtils::LIGHT_SQUARES != 0 {
+			true => [SquareUtils::A8, SquareUtils::H1],
+			false => [SquareUtils::A1, SquareUtils::H8],
+		};
+		let corner_distance = corners
+			.map(|corner| SquareUtils::distance(self.weak_king, corner))
+			.into_iter()
+			.min()
+			.unwrap();
+
+		KNOWN_WIN + 20 * (7 - corner_distance as Score) + self.closeness()
+	}
+
+	/// Bishops and rook pawns draw when the lone king holds a queening square of the colour the
+	/// bishops cannot cover.
+	fn wrong_bishop(&self, signature: Signature) -> Option<Score> {
+		let pawns = self.board.pieces[self.strong][PieceUtils::PAWN];
+		let bishops = self.board.pieces[self.strong][PieceUtils::BISHOP];
+
+		if signature & !SignatureUtils::PAWNS != bishops.count_ones() * SignatureUtils::BISHOP {
+			return None;
+		}
+
+		let file = match pawns {
+			_ if pawns & !BitboardUtils::FILES[FileUtils::A] == 0 => FileUtils::A,
+			_ if pawns & !BitboardUtils::FILES[FileUtils::H] == 0 => FileUtils::H,
+			_ => return None,
+		};
+
+		let rank = match self.strong {
+			ColorUtils::WHITE => RankUtils::R8,
+			_ => RankUtils::R1,
+		};
+
+		let queening = SquareUtils::from_location(file, rank);
+		let queening_light = BitboardUtils::LIGHT_SQUARES & BitboardUtils::SQUARES[queening] != 0;
+
+		let covered = match queening_light {
+			true => bishops & BitboardUtils::LIGHT_SQUARES,
+			false => bishops & BitboardUtils::DARK_SQUARES,
+		};
+
+		match covered == 0 && SquareUtils::distance(self.weak_king, queening) <= 1 {
+			true => Some(0),
+			false => None,
+		}
+	}
+
+	fn closeness(&self) -> Score {
+		10 * (7 - SquareUtils::distance(self.strong_king, self.weak_king) as Score)
+	}
+}
+
+/// How far the square is from the centre, from 0 in the centre to 6 in a corner.
+fn edge_distance(square: Square) -> Score {
+	let (file, rank) = SquareUtils::location(square);
+
+	(3 - file.min(7 - file) + 3 - rank.min(7 - rank)) as Score
+}
+
+fn relative_rank(color: Color, square: Square) -> usize {
+	let (_, rank) = SquareUtils::location(square);
+
+	match color {
+		ColorUtils::WHITE => rank,
+		_ => 7 - rank,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{search::SearchLimits, Chess};
+	use std::sync::{atomic::AtomicBool, Arc};
+
+	fn recognized(fen: &str) -> Option<Score> {
+		let chess = Chess::try_from(fen).unwrap();
+
+		recognize(&chess.board, &chess.move_gen)
+	}
+
+	#[test]
+	fn known_endings() {
+		// A rook pawn with the lone king in front of it, and a bishop of the wrong colour.
+		assert_eq!(recognized("k7/8/K7/P7/8/8/8/8 w - - 0 1"), Some(0));
+		assert_eq!(recognized("7k/8/6KP/8/8/3B4/8/8 w - - 0 1"), Some(0));
+		assert_eq!(recognized("8/8/3k4/8/8/3KN3/8/8 b - - 0 1"), Some(0));
+
+		// The lone king takes a hanging queen.
+		assert_eq!(recognized("8/8/8/8/8/8/1kQ5/4K3 b - - 0 1"), Some(0));
+
+		// Wins score for the strong side, whichever side is to move.
+		let kpk = recognized("8/8/8/8/8/k7/4P3/4K3 w - - 0 1").unwrap();
+		let krk = recognized("8/8/8/4k3/8/8/8/R3K3 b - - 0 1").unwrap();
+
+		assert!(kpk >= KNOWN_WIN);
+		assert!(krk <= -KNOWN_WIN);
+
+		// A queen scores higher the nearer its mate.
+		let mate_in_one = recognized("k7/8/1K6/8/8/8/7Q/8 w - - 0 1").unwrap();
+		let mate_in_two = recognized("1k6/8/1K6/8/8/8/7Q/8 w - - 0 1").unwrap();
+
+		assert!(mate_in_one > mate_in_two);
+	}
+
+	/// Every queen or rook ending scores above every won KPK position, so the pawn promotes.
+	#[test]
+	fn promotion_improves_kpk_wins() {
+		let best_kpk = KNOWN_WIN + 6 * 20;
+		let queen = recognized("Q7/8/8/8/8/8/8/1k5K w - - 0 1").unwrap();
+		let rook = recognized("R7/8/8/8/8/8/8/1k5K w - - 0 1").unwrap();
+		let queen_and_pawn = recognized("Q7/8/8/8/8/8/P7/1k5K w - - 0 1").unwrap();
+
+		assert!(best_kpk < rook && rook < queen && queen < queen_and_pawn);
+	}
+
+	#[test]
+	fn engine_promotes_in_kpk() {
+		let mut chess = Chess::try_from("8/P7/8/8/5k2/8/8/7K w - - 0 1").unwrap();
+
+		let limits = SearchLimits {
+			depth: Some(7),
+			..SearchLimits::default()
+		};
+		let result = chess.search(limits, Arc::new(AtomicBool::new(false)), |_| {});
+
+		assert_eq!(result.best_move, chess.parse_uci_move("a7a8q").ok());
+	}
+}
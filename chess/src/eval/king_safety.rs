@@ -0,0 +1,60 @@
+use super::{
+	pawns::{adjacent_files, forward_ranks},
+	Evaluator, Tapered,
+};
+use crate::board::{
+	bitboard::BitboardUtils,
+	color::ColorUtils,
+	file_rank::{FileUtils, RankUtils},
+	piece::PieceUtils,
+	square::SquareUtils,
+	Color,
+};
+
+impl Evaluator<'_> {
+	pub(super) fn king_safety(&self, color: Color) -> Tapered {
+		let board = self.board;
+		let params = self.params;
+		let enemy = color ^ 1;
+
+		let king = BitboardUtils::lsb(board.pieces[color][PieceUtils::KING]);
+		let (file, rank) = SquareUtils::location(king);
+		let pawns = board.pieces[color][PieceUtils::PAWN];
+
+		let mut score = Tapered::default();
+
+		let files = BitboardUtils::FILES[file] | adjacent_files(file);
+		let beyond = match color {
+			ColorUtils::WHITE if rank + 2 <= RankUtils::R8 => forward_ranks(color, rank + 2),
+			ColorUtils::BLACK if rank >= RankUtils::R3 => forward_ranks(color, rank - 2),
+			_ => BitboardUtils::EMPTY,
+		};
+		let shield = files & forward_ranks(color, rank) & !beyond;
+
+		score += params.king_shield * (pawns & shield).count_ones() as i32;
+
+		for file in file.saturating_sub(1)..=(file + 1).min(FileUtils::H) {
+			if pawns & BitboardUtils::FILES[file] == 0 {
+				score += params.king_open_file;
+			}
+		}
+
+		let zone = self
+			.move_gen
+			.attacks(PieceUtils::KING, color, king, board.occupancy)
+			| BitboardUtils::SQUARES[king];
+
+		for piece in PieceUtils::RANGE {
+			let mut pieces = board.pieces[enemy][piece];
+
+			while pieces > 0 {
+				let square = BitboardUtils::pop_lsb(&mut pieces);
+				let attacks = self.move_gen.attacks(piece, enemy, square, board.occupancy);
+
+				score += params.king_attack[piece] * (attacks & zone).count_ones() as i32;
+			}
+		}
+
+		score
+	}
+}
@@ -0,0 +1,285 @@
+use crate::{
+	board::{
+		bitboard::BitboardUtils, color::ColorUtils, file_rank::FileUtils, piece::PieceUtils,
+		square::SquareUtils, Board, Color, Piece, Square,
+	},
+	move_gen::MoveGen,
+	move_list::MoveList,
+};
+use std::sync::OnceLock;
+
+/// The pawn squares of the bitbase: ranks 2 to 7 of files a to d, since the other files are
+/// their mirror image.
+const PAWN_SQUARES: usize = 24;
+const SIZE: usize = ColorUtils::SIZE * SquareUtils::SIZE * SquareUtils::SIZE * PAWN_SQUARES;
+
+/// Positions outside the bitbase that a move can lead to, after a promotion or the capture of
+/// the pawn.
+const WIN: usize = SIZE;
+const DRAW: usize = SIZE + 1;
+
+static KPK: OnceLock<Kpk> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+	Unknown,
+	Draw,
+	Win,
+}
+
+/// A bitbase of king and pawn against king, with a bit per position set when the side with the
+/// pawn wins.
+pub(crate) struct Kpk {
+	wins: Vec<u64>,
+}
+
+impl Kpk {
+	/// The bitbase, generated by retrograde analysis the first time it is needed.
+	pub(crate) fn get(move_gen: &MoveGen) -> &'static Self {
+		KPK.get_or_init(|| Self::generate(move_gen))
+	}
+
+	/// Whether the side with the pawn wins with `to_move` to move.
+	pub(crate) fn probe(
+		&self,
+		strong: Color,
+		strong_king: Square,
+		pawn: Square,
+		weak_king: Square,
+		to_move: Color,
+	) -> bool {
+		let flip_rank = match strong {
+			ColorUtils::WHITE => 0,
+			_ => 56,
+		};
+		let flip_file = match SquareUtils::location(pawn).0 > FileUtils::D {
+			true => 7,
+			false => 0,
+		};
+		let normalize = |square: Square| square ^ flip_rank ^ flip_file;
+
+		let color = match to_move == strong {
+			true => ColorUtils::WHITE,
+			false => ColorUtils::BLACK,
+		};
+		let index = index(
+			color,
+			normalize(strong_king),
+			normalize(weak_king),
+			normalize(pawn),
+		);
+
+		self.wins[index / 64] & (1 << (index % 64)) != 0
+	}
+
+	/// Links every position to the positions its moves lead to, then repeatedly marks white to
+	/// move positions won when a move wins and black to move positions won when every move
+	/// loses, until nothing changes. Positions left unresolved are draws.
+	fn generate(move_gen: &MoveGen) -> Self {
+		let empty = Board::from("8/8/8/8/8/8/8/8 w - - 0 1");
+
+		let mut states = vec![State::Unknown; SIZE + 2];
+		let mut successors = Vec::new();
+		let mut ranges = vec![(0, 0); SIZE];
+
+		states[WIN] = State::Win;
+		states[DRAW] = State::Draw;
+
+		for (index, range) in ranges.iter_mut().enumerate() {
+			let start = successors.len();
+
+			match position(&empty, move_gen, index) {
+				Some(board) => successors.extend(moves(&board, move_gen, &mut states[index])),
+				None => states[index] = State::Draw,
+			}
+
+			*range = (start, successors.len());
+		}
+
+		let mut changed = true;
+
+		while changed {
+			changed = false;
+
+			for (index, &(start, end)) in ranges.iter().enumerate() {
+				if states[index] != State::Unknown {
+					continue;
+				}
+
+				let results = &successors[start..end];
+				let any = |state| results.iter().any(|&next| states[next] == state);
+				let all = |state| results.iter().all(|&next| states[next] == state);
+
+				let state = match index < SIZE / 2 {
+					true if any(State::Win) => State::Win,
+					true if all(State::Draw) => State::Draw,
+					false if any(State::Draw) => State::Draw,
+					false if all(State::Win) => State::Win,
+					_ => continue,
+				};
+
+				states[index] = state;
+				changed = true;
+			}
+		}
+
+		let mut wins = vec![0; SIZE.div_ceil(64)];
+
+		for (index, state) in states[..SIZE].iter().enumerate() {
+			if *state == State::Win {
+				wins[index / 64] |= 1 << (index % 64);
+			}
+		}
+
+		Self { wins }
+	}
+}
+
+#[inline(always)]
+fn index(color: Color, strong_king: Square, weak_king: Square, pawn: Square) -> usize {
+	let (file, rank) = SquareUtils::location(pawn);
+	let pawn = (rank - 1) * 4 + file;
+
+	((color * SquareUtils::SIZE + strong_king) * SquareUtils::SIZE + weak_king) * PAWN_SQUARES
+		+ pawn
+}
+
+/// The legal position of an index, with white as the side with the pawn.
+fn position(empty: &Board, move_gen: &MoveGen, index: usize) -> Option<Board> {
+	let pawn = index % PAWN_SQUARES;
+	let pawn = SquareUtils::from_location(pawn % 4, pawn / 4 + 1);
+	let weak_king = index / PAWN_SQUARES % SquareUtils::SIZE;
+	let strong_king = index / PAWN_SQUARES / SquareUtils::SIZE % SquareUtils::SIZE;
+	let color = index / PAWN_SQUARES / SquareUtils::SIZE / SquareUtils::SIZE;
+
+	if strong_king == pawn || weak_king == pawn || SquareUtils::distance(strong_king, weak_king) < 2
+	{
+		return None;
+	}
+
+	let mut board = empty.clone();
+
+	board.add_piece(PieceUtils::KING, ColorUtils::WHITE, strong_king);
+	board.add_piece(PieceUtils::PAWN, ColorUtils::WHITE, pawn);
+	board.add_piece(PieceUtils::KING, ColorUtils::BLACK, weak_king);
+	board.color = color;
+
+	let in_check = move_gen.square_attacked(&board, ColorUtils::WHITE, weak_king);
+
+	match color == ColorUtils::WHITE && in_check {
+		true => None,
+		false => Some(board),
+	}
+}
+
+/// The positions the moves of a position lead to. A mate or stalemate settles the state of
+/// the position instead.
+fn moves(board: &Board, move_gen: &MoveGen, state: &mut State) -> Vec<usize> {
+	let mut list = MoveList::default();
+	move_gen.legal(board, &mut list);
+
+	let strong_king = BitboardUtils::lsb(board.pieces[ColorUtils::WHITE][PieceUtils::KING]);
+	let weak_king = BitboardUtils::lsb(board.pieces[ColorUtils::BLACK][PieceUtils::KING]);
+	let pawn = BitboardUtils::lsb(board.pieces[ColorUtils::WHITE][PieceUtils::PAWN]);
+
+	if list.len() == 0 {
+		let in_check = move_gen.square_attacked(board, ColorUtils::WHITE, weak_king);
+
+		*state = match board.color == ColorUtils::BLACK && in_check {
+			true => State::Win,
+			false => State::Draw,
+		};
+	}
+
+	list.iter()
+		.map(|m| match (board.color, m.piece()) {
+			(ColorUtils::WHITE, PieceUtils::KING) => {
+				index(ColorUtils::BLACK, m.to(), weak_king, pawn)
+			}
+			(ColorUtils::WHITE, _) if m.promoted() != PieceUtils::NONE => {
+				promotion(board, move_gen, m.to(), m.promoted())
+			}
+			(ColorUtils::WHITE, _) => index(ColorUtils::BLACK, strong_king, weak_king, m.to()),
+			(_, _) if m.captured() == PieceUtils::PAWN => DRAW,
+			(_, _) => index(ColorUtils::WHITE, strong_king, m.to(), pawn),
+		})
+		.collect()
+}
+
+/// Whether promoting wins: a new queen or rook wins unless it is captured or stalemates.
+fn promotion(board: &Board, move_gen: &MoveGen, square: Square, promoted: Piece) -> usize {
+	if promoted != PieceUtils::QUEEN && promoted != PieceUtils::ROOK {
+		return DRAW;
+	}
+
+	let pawn = BitboardUtils::lsb(board.pieces[ColorUtils::WHITE][PieceUtils::PAWN]);
+	let weak_king = BitboardUtils::lsb(board.pieces[ColorUtils::BLACK][PieceUtils::KING]);
+
+	let mut board = board.clone();
+	board.remove_piece(PieceUtils::PAWN, ColorUtils::WHITE, pawn);
+	board.add_piece(promoted, ColorUtils::WHITE, square);
+	board.color = ColorUtils::BLACK;
+
+	let mut list = MoveList::default();
+	move_gen.legal(&board, &mut list);
+
+	let in_check = move_gen.square_attacked(&board, ColorUtils::WHITE, weak_king);
+
+	match list.len() {
+		0 if in_check => WIN,
+		0 => DRAW,
+		_ if list.iter().any(|m| m.to() == square) => DRAW,
+		_ => WIN,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::retrograde::{Dtm, Solver};
+
+	/// Every legal position of the bitbase agrees with the solved KPvK table.
+	#[test]
+	fn matches_retrograde_table() {
+		let move_gen = MoveGen::default();
+		let kpk = Kpk::generate(&move_gen);
+
+		let mut solver = Solver::default();
+		let table = solver.solve(&"KPvK".parse().unwrap());
+		let empty = Board::from("8/8/8/8/8/8/8/8 w - - 0 1");
+		let mut wins = 0;
+
+		for index in 0..SIZE {
+			let Some(board) = position(&empty, &move_gen, index) else {
+				continue;
+			};
+
+			let strong_king = BitboardUtils::lsb(board.pieces[ColorUtils::WHITE][PieceUtils::KING]);
+			let weak_king = BitboardUtils::lsb(board.pieces[ColorUtils::BLACK][PieceUtils::KING]);
+			let pawn = BitboardUtils::lsb(board.pieces[ColorUtils::WHITE][PieceUtils::PAWN]);
+
+			let won = kpk.probe(ColorUtils::WHITE, strong_king, pawn, weak_king, board.color);
+			let expected = match table.probe(&board).unwrap() {
+				Dtm::Win(_) => board.color == ColorUtils::WHITE,
+				Dtm::Loss(_) => board.color == ColorUtils::BLACK,
+				Dtm::Draw => false,
+			};
+
+			assert_eq!(won, expected, "{}", board.to_fen());
+
+			// The same position mirrored and with the colours swapped.
+			let mirrored = kpk.probe(
+				ColorUtils::BLACK,
+				strong_king ^ 56 ^ 7,
+				pawn ^ 56 ^ 7,
+				weak_king ^ 56 ^ 7,
+				board.color ^ 1,
+			);
+
+			assert_eq!(mirrored, won, "{}", board.to_fen());
+			wins += won as usize;
+		}
+
+		assert!(wins > 0);
+	}
+}
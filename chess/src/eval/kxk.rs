@@ -0,0 +1,44 @@
+use crate::{
+	board::{piece::PieceUtils, Board, Piece},
+	retrograde::{Dtm, Solver, Table},
+};
+use std::sync::OnceLock;
+
+static KXK: OnceLock<Kxk> = OnceLock::new();
+
+/// The distance to mate of every queen or rook against lone king position, solved by
+/// retrograde analysis.
+pub(crate) struct Kxk {
+	queen: Table,
+	rook: Table,
+}
+
+impl Kxk {
+	/// The tables, solved the first time they are needed.
+	pub(crate) fn get() -> &'static Self {
+		KXK.get_or_init(|| {
+			let mut solver = Solver::default();
+			let mut solve = |material: &str| {
+				let material = material.parse().unwrap();
+				solver.solve(&material);
+				solver.remove(&material).unwrap()
+			};
+
+			Self {
+				queen: solve("KQvK"),
+				rook: solve("KRvK"),
+			}
+		})
+	}
+
+	/// The distance to mate for the side to move of a board with a king and `piece` against a
+	/// lone king.
+	pub(crate) fn probe(&self, board: &Board, piece: Piece) -> Dtm {
+		let table = match piece {
+			PieceUtils::QUEEN => &self.queen,
+			_ => &self.rook,
+		};
+
+		table.probe(board).unwrap_or(Dtm::Draw)
+	}
+}
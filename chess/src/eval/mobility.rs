@@ -0,0 +1,33 @@
+use super::{Evaluator, Tapered};
+use crate::board::{bitboard::BitboardUtils, piece::PieceUtils, Color};
+
+impl Evaluator<'_> {
+	pub(super) fn mobility(&self, color: Color) -> Tapered {
+		let board = self.board;
+		let ally = board.occupancy_color[color];
+
+		let mut score = Tapered::default();
+
+		for piece in [
+			PieceUtils::KNIGHT,
+			PieceUtils::BISHOP,
+			PieceUtils::ROOK,
+			PieceUtils::QUEEN,
+		] {
+			let mut pieces = board.pieces[color][piece];
+
+			while pieces > 0 {
+				let square = BitboardUtils::pop_lsb(&mut pieces);
+				let moves = self.move_gen.attacks(piece, color, square, board.occupancy) & !ally;
+
+				score += self.params.mobility[piece] * moves.count_ones() as i32;
+			}
+		}
+
+		if board.pieces[color][PieceUtils::BISHOP].count_ones() >= 2 {
+			score += self.params.bishop_pair;
+		}
+
+		score
+	}
+}
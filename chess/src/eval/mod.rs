@@ -0,0 +1,109 @@
+mod endgame;
+mod king_safety;
+mod kpk;
+mod kxk;
+mod mobility;
+mod params;
+mod pawns;
+mod psqt;
+mod tapered;
+
+use crate::{
+	board::{bitboard::BitboardUtils, color::ColorUtils, piece::PieceUtils, Board, Color},
+	move_gen::MoveGen,
+	search::Score,
+	Chess,
+};
+use std::sync::Arc;
+
+pub use endgame::{Signature, SignatureUtils};
+pub use params::EvalParams;
+pub use tapered::Tapered;
+
+impl Board {
+	/// Scores the position from the side to move's point of view, in centipawns.
+	pub fn evaluate(&self, move_gen: &MoveGen, params: &EvalParams) -> Score {
+		if let Some(score) = endgame::recognize(self, move_gen) {
+			return score;
+		}
+
+		let evaluator = Evaluator {
+			board: self,
+			move_gen,
+			params,
+		};
+
+		let score = evaluator.side(ColorUtils::WHITE) - evaluator.side(ColorUtils::BLACK);
+		let score = score.taper(evaluator.phase(), params.max_phase);
+
+		match self.color {
+			ColorUtils::WHITE => score,
+			_ => -score,
+		}
+	}
+}
+
+impl Chess {
+	#[inline(always)]
+	pub fn evaluate(&self) -> Score {
+		self.board.evaluate(&self.move_gen, &self.eval_params)
+	}
+
+	pub fn set_eval_params(&mut self, params: EvalParams) {
+		self.eval_params = Arc::new(params);
+	}
+
+	/// Generates the KPK bitbase and the queen and rook mate tables ahead of time. They are
+	/// otherwise generated by the first evaluation that needs them, which takes a moment.
+	pub fn init_bitbases(&self) {
+		kpk::Kpk::get(&self.move_gen);
+		kxk::Kxk::get();
+	}
+}
+
+struct Evaluator<'a> {
+	board: &'a Board,
+	move_gen: &'a MoveGen,
+	params: &'a EvalParams,
+}
+
+impl Evaluator<'_> {
+	fn side(&self, color: Color) -> Tapered {
+		self.material(color)
+			+ self.pawn_structure(color)
+			+ self.mobility(color)
+			+ self.king_safety(color)
+	}
+
+	fn material(&self, color: Color) -> Tapered {
+		let mut score = Tapered::default();
+
+		for piece in PieceUtils::RANGE {
+			let mut pieces = self.board.pieces[color][piece];
+
+			while pieces > 0 {
+				let square = match color {
+					ColorUtils::WHITE => BitboardUtils::pop_lsb(&mut pieces),
+					_ => BitboardUtils::pop_lsb(&mut pieces) ^ 56,
+				};
+
+				score += self.params.material[piece] + self.params.psqt[piece][square];
+			}
+		}
+
+		score
+	}
+
+	fn phase(&self) -> Score {
+		let board = self.board;
+
+		PieceUtils::RANGE
+			.map(|piece| {
+				let count = board.pieces[ColorUtils::WHITE][piece].count_ones()
+					+ board.pieces[ColorUtils::BLACK][piece].count_ones();
+
+				count as Score * self.params.phase[piece]
+			})
+			.sum()
+	}
+}
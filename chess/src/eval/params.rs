@@ -0,0 +1,94 @@
+use super::{psqt, Tapered};
+use crate::{
+	board::{file_rank::RankUtils, piece::PieceUtils, square::SquareUtils},
+	search::Score,
+};
+
+/// Every weight used by the evaluation, from white's point of view and in centipawns.
+#[derive(Debug, Clone)]
+pub struct EvalParams {
+	pub material: [Tapered; PieceUtils::SIZE],
+	/// Indexed by `[piece][square]` as seen from white, black mirrors the square vertically.
+	pub psqt: [[Tapered; SquareUtils::SIZE]; PieceUtils::SIZE],
+	/// Contribution of each piece to the game phase, the sum at the start position is `max_phase`.
+	pub phase: [Score; PieceUtils::SIZE],
+	pub max_phase: Score,
+
+	pub doubled_pawn: Tapered,
+	pub isolated_pawn: Tapered,
+	/// Indexed by the rank of the pawn relative to its own side.
+	pub passed_pawn: [Tapered; RankUtils::SIZE],
+
+	/// Bonus per reachable square not occupied by an ally.
+	pub mobility: [Tapered; PieceUtils::SIZE],
+	pub bishop_pair: Tapered,
+
+	/// Bonus per ally pawn shielding the king.
+	pub king_shield: Tapered,
+	/// Penalty per file next to the king without an ally pawn.
+	pub king_open_file: Tapered,
+	/// Penalty per attacked square around the king, indexed by the attacking piece.
+	pub king_attack: [Tapered; PieceUtils::SIZE],
+}
+
+impl Default for EvalParams {
+	fn default() -> Self {
+		let mut psqt = [[Tapered::default(); SquareUtils::SIZE]; PieceUtils::SIZE];
+
+		for piece in PieceUtils::RANGE {
+			for square in SquareUtils::RANGE {
+				let index = square ^ 56;
+
+				psqt[piece][square] = Tapered::new(psqt::MG[piece][index], psqt::EG[piece][index]);
+			}
+		}
+
+		Self {
+			material: [
+				Tapered::new(82, 94),
+				Tapered::new(337, 281),
+				Tapered::new(365, 297),
+				Tapered::new(477, 512),
+				Tapered::new(1025, 936),
+				Tapered::new(0, 0),
+			],
+			psqt,
+			phase: [0, 1, 1, 2, 4, 0],
+			max_phase: 24,
+
+			doubled_pawn: Tapered::new(-10, -20),
+			isolated_pawn: Tapered::new(-12, -15),
+			passed_pawn: [
+				Tapered::new(0, 0),
+				Tapered::new(5, 10),
+				Tapered::new(10, 15),
+				Tapered::new(15, 30),
+				Tapered::new(25, 50),
+				Tapered::new(40, 80),
+				Tapered::new(60, 120),
+				Tapered::new(0, 0),
+			],
+
+			mobility: [
+				Tapered::new(0, 0),
+				Tapered::new(4, 4),
+				Tapered::new(5, 5),
+				Tapered::new(2, 4),
+				Tapered::new(1, 2),
+				Tapered::new(0, 0),
+			],
+			bishop_pair: Tapered::new(30, 50),
+
+			king_shield: Tapered::new(10, 0),
+			king_open_file: Tapered::new(-20, 0),
+			king_attack: [
+				Tapered::new(-5, 0),
+				Tapered::new(-8, 0),
+				Tapered::new(-8, 0),
+				Tapered::new(-10, 0),
+				Tapered::new(-15, 0),
+				Tapered::new(0, 0),
+			],
+		}
+	}
+}
@@ -0,0 +1,77 @@
+use super::{Evaluator, Tapered};
+use crate::board::{
+	bitboard::BitboardUtils,
+	color::ColorUtils,
+	file_rank::{FileUtils, RankUtils},
+	piece::PieceUtils,
+	square::SquareUtils,
+	Bitboard, Color, File, Rank,
+};
+
+impl Evaluator<'_> {
+	pub(super) fn pawn_structure(&self, color: Color) -> Tapered {
+		let params = self.params;
+		let pawns = self.board.pieces[color][PieceUtils::PAWN];
+		let enemy_pawns = self.board.pieces[color ^ 1][PieceUtils::PAWN];
+
+		let mut score = Tapered::default();
+
+		for file in FileUtils::RANGE {
+			let count = (pawns & BitboardUtils::FILES[file]).count_ones() as i32;
+
+			if count > 1 {
+				score += params.doubled_pawn * (count - 1);
+			}
+		}
+
+		let mut bitboard = pawns;
+
+		while bitboard > 0 {
+			let square = BitboardUtils::pop_lsb(&mut bitboard);
+			let (file, rank) = SquareUtils::location(square);
+			let neighbours = adjacent_files(file);
+
+			if pawns & neighbours == 0 {
+				score += params.isolated_pawn;
+			}
+
+			let front_span = (BitboardUtils::FILES[file] | neighbours) & forward_ranks(color, rank);
+
+			if enemy_pawns & front_span == 0 {
+				score += params.passed_pawn[relative_rank(color, rank)];
+			}
+		}
+
+		score
+	}
+}
+
+pub(super) fn adjacent_files(file: File) -> Bitboard {
+	let mut files = BitboardUtils::EMPTY;
+
+	if file > FileUtils::A {
+		files |= BitboardUtils::FILES[file - 1];
+	}
+
+	if file < FileUtils::H {
+		files |= BitboardUtils::FILES[file + 1];
+	}
+
+	files
+}
+
+/// Every rank strictly in front of `rank` from `color`'s point of view.
+pub(super) fn forward_ranks(color: Color, rank: Rank) -> Bitboard {
+	match color {
+		ColorUtils::WHITE if rank < RankUtils::R8 => !0 << ((rank + 1) * 8),
+		ColorUtils::BLACK => (1 << (rank * 8)) - 1,
+		_ => BitboardUtils::EMPTY,
+	}
+}
+
+pub(super) fn relative_rank(color: Color, rank: Rank) -> Rank {
+	match color {
+		ColorUtils::WHITE => rank,
+		_ => RankUtils::R8 - rank,
+	}
+}
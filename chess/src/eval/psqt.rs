@@ -0,0 +1,159 @@
+use crate::{
+	board::{piece::PieceUtils, square::SquareUtils},
+	search::Score,
+};
+
+pub type PieceSquareTable = [Score; SquareUtils::SIZE];
+
+// Tables are laid out as seen from white's side of the board, so a8 comes first and h1 last.
+// White looks a square up with `square ^ 56`, black uses the square as is.
+
+#[rustfmt::skip]
+const PAWN_MG: PieceSquareTable = [
+	  0,   0,   0,   0,   0,   0,   0,   0,
+	 50,  50,  50,  50,  50,  50,  50,  50,
+	 10,  10,  20,  30,  30,  20,  10,  10,
+	  5,   5,  10,  25,  25,  10,   5,   5,
+	  0,   0,   0,  20,  20,   0,   0,   0,
+	  5,  -5, -10,   0,   0, -10,  -5,   5,
+	  5,  10,  10, -20, -20,  10,  10,   5,
+	  0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const PAWN_EG: PieceSquareTable = [
+	  0,   0,   0,   0,   0,   0,   0,   0,
+	 60,  60,  60,  60,  60,  60,  60,  60,
+	 40,  40,  40,  40,  40,  40,  40,  40,
+	 25,  25,  25,  25,  25,  25,  25,  25,
+	 12,  12,  12,  12,  12,  12,  12,  12,
+	  5,   5,   5,   5,   5,   5,   5,   5,
+	  0,   0,   0,   0,   0,   0,   0,   0,
+	  0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const KNIGHT_MG: PieceSquareTable = [
+	-50, -40, -30, -30, -30, -30, -40, -50,
+	-40, -20,   0,   0,   0,   0, -20, -40,
+	-30,   0,  10,  15,  15,  10,   0, -30,
+	-30,   5,  15,  20,  20,  15,   5, -30,
+	-30,   0,  15,  20,  20,  15,   0, -30,
+	-30,   5,  10,  15,  15,  10,   5, -30,
+	-40, -20,   0,   5,   5,   0, -20, -40,
+	-50, -40, -30, -30, -30, -30, -40, -50,
+];
+
+#[rustfmt::skip]
+const KNIGHT_EG: PieceSquareTable = [
+	-40, -30, -20, -20, -20, -20, -30, -40,
+	-30, -15,   0,   0,   0,   0, -15, -30,
+	-20,   0,  10,  12,  12,  10,   0, -20,
+	-20,   5,  12,  15,  15,  12,   5, -20,
+	-20,   0,  12,  15,  15,  12,   0, -20,
+	-20,   5,  10,  12,  12,  10,   5, -20,
+	-30, -15,   0,   5,   5,   0, -15, -30,
+	-40, -30, -20, -20, -20, -20, -30, -40,
+];
+
+#[rustfmt::skip]
+const BISHOP_MG: PieceSquareTable = [
+	-20, -10, -10, -10, -10, -10, -10, -20,
+	-10,   0,   0,   0,   0,   0,   0, -10,
+	-10,   0,   5,  10,  10,   5,   0, -10,
+	-10,   5,   5,  10,  10,   5,   5, -10,
+	-10,   0,  10,  10,  10,  10,   0, -10,
+	-10,  10,  10,  10,  10,  10,  10, -10,
+	-10,   5,   0,   0,   0,   0,   5, -10,
+	-20, -10, -10, -10, -10, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const BISHOP_EG: PieceSquareTable = [
+	-15, -10, -10, -10, -10, -10, -10, -15,
+	-10,   0,   0,   0,   0,   0,   0, -10,
+	-10,   0,   5,   5,   5,   5,   0, -10,
+	-10,   0,   5,  10,  10,   5,   0, -10,
+	-10,   0,   5,  10,  10,   5,   0, -10,
+	-10,   0,   5,   5,   5,   5,   0, -10,
+	-10,   0,   0,   0,   0,   0,   0, -10,
+	-15, -10, -10, -10, -10, -10, -10, -15,
+];
+
+#[rustfmt::skip]
+const ROOK_MG: PieceSquareTable = [
+	  0,   0,   0,   0,   0,   0,   0,   0,
+	  5,  10,  10,  10,  10,  10,  10,   5,
+	 -5,   0,   0,   0,   0,   0,   0,  -5,
+	 -5,   0,   0,   0,   0,   0,   0,  -5,
+	 -5,   0,   0,   0,   0,   0,   0,  -5,
+	 -5,   0,   0,   0,   0,   0,   0,  -5,
+	 -5,   0,   0,   0,   0,   0,   0,  -5,
+	  0,   0,   0,   5,   5,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const ROOK_EG: PieceSquareTable = [
+	  5,   5,   5,   5,   5,   5,   5,   5,
+	 10,  10,  10,  10,  10,  10,  10,  10,
+	  0,   0,   0,   0,   0,   0,   0,   0,
+	  0,   0,   0,   0,   0,   0,   0,   0,
+	  0,   0,   0,   0,   0,   0,   0,   0,
+	  0,   0,   0,   0,   0,   0,   0,   0,
+	  0,   0,   0,   0,   0,   0,   0,   0,
+	  0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const QUEEN_MG: PieceSquareTable = [
+	-20, -10, -10,  -5,  -5, -10, -10, -20,
+	-10,   0,   0,   0,   0,   0,   0, -10,
+	-10,   0,   5,   5,   5,   5,   0, -10,
+	 -5,   0,   5,   5,   5,   5,   0,  -5,
+	  0,   0,   5,   5,   5,   5,   0,  -5,
+	-10,   5,   5,   5,   5,   5,   0, -10,
+	-10,   0,   5,   0,   0,   0,   0, -10,
+	-20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const QUEEN_EG: PieceSquareTable = [
+	-20, -10, -10,  -5,  -5, -10, -10, -20,
+	-10,   0,   5,   5,   5,   5,   0, -10,
+	-10,   5,  10,  10,  10,  10,   5, -10,
+	 -5,   5,  10,  15,  15,  10,   5,  -5,
+	 -5,   5,  10,  15,  15,  10,   5,  -5,
+	-10,   5,  10,  10,  10,  10,   5, -10,
+	-10,   0,   5,   5,   5,   5,   0, -10,
+	-20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const KING_MG: PieceSquareTable = [
+	-30, -40, -40, -50, -50, -40, -40, -30,
+	-30, -40, -40, -50, -50, -40, -40, -30,
+	-30, -40, -40, -50, -50, -40, -40, -30,
+	-30, -40, -40, -50, -50, -40, -40, -30,
+	-20, -30, -30, -40, -40, -30, -30, -20,
+	-10, -20, -20, -20, -20, -20, -20, -10,
+	 20,  20,   0,   0,   0,   0,  20,  20,
+	 20,  30,  10,   0,   0,  10,  30,  20,
+];
+
+#[rustfmt::skip]
+const KING_EG: PieceSquareTable = [
+	-50, -40, -30, -20, -20, -30, -40, -50,
+	-30, -20, -10,   0,   0, -10, -20, -30,
+	-30, -10,  20,  30,  30,  20, -10, -30,
+	-30, -10,  30,  40,  40,  30, -10, -30,
+	-30, -10,  30,  40,  40,  30, -10, -30,
+	-30, -10,  20,  30,  30,  20, -10, -30,
+	-30, -30,   0,   0,   0,   0, -30, -30,
+	-50, -30, -30, -30, -30, -30, -30, -50,
+];
+
+pub const MG: [PieceSquareTable; PieceUtils::SIZE] =
+	[PAWN_MG, KNIGHT_MG, BISHOP_MG, ROOK_MG, QUEEN_MG, KING_MG];
+
+pub const EG: [PieceSquareTable; PieceUtils::SIZE] =
+	[PAWN_EG, KNIGHT_EG, BISHOP_EG, ROOK_EG, QUEEN_EG, KING_EG];
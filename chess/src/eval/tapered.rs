@@ -0,0 +1,72 @@
+use crate::search::Score;
+use std::ops;
+
+/// A pair of middlegame and endgame scores, blended by game phase.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Tapered {
+	pub mg: Score,
+	pub eg: Score,
+}
+
+impl Tapered {
+	pub const fn new(mg: Score, eg: Score) -> Self {
+		Self { mg, eg }
+	}
+
+	#[inline(always)]
+	pub fn taper(&self, phase: Score, max_phase: Score) -> Score {
+		let phase = phase.min(max_phase);
+
+		(self.mg * phase + self.eg * (max_phase - phase)) / max_phase
+	}
+}
+
+impl ops::Add for Tapered {
+	type Output = Self;
+
+	#[inline(always)]
+	fn add(self, rhs: Self) -> Self::Output {
+		Self::new(self.mg + rhs.mg, self.eg + rhs.eg)
+	}
+}
+
+impl ops::AddAssign for Tapered {
+	#[inline(always)]
+	fn add_assign(&mut self, rhs: Self) {
+		*self = *self + rhs;
+	}
+}
+
+impl ops::Sub for Tapered {
+	type Output = Self;
+
+	#[inline(always)]
+	fn sub(self, rhs: Self) -> Self::Output {
+		Self::new(self.mg - rhs.mg, self.eg - rhs.eg)
+	}
+}
+
+impl ops::SubAssign for Tapered {
+	#[inline(always)]
+	fn sub_assign(&mut self, rhs: Self) {
+		*self = *self - rhs;
+	}
+}
+
+impl ops::Mul<Score> for Tapered {
+	type Output = Self;
+
+	#[inline(always)]
+	fn mul(self, rhs: Score) -> Self::Output {
+		Self::new(self.mg * rhs, self.eg * rhs)
+	}
+}
+
+impl ops::Neg for Tapered {
+	type Output = Self;
+
+	#[inline(always)]
+	fn neg(self) -> Self::Output {
+		Self::new(-self.mg, -self.eg)
+	}
+}
@@ -1,33 +1,59 @@
 mod history;
 mod move_list;
-mod perft;
 mod playmove;
 mod transposition;
 
+use board::{bitboard::BitboardUtils, piece::PieceUtils};
 use move_list::MoveList;
-use std::{fmt, sync::Arc};
+use std::{fmt, str::FromStr, sync::Arc};
 
 pub mod board;
+pub mod book;
+pub mod epd;
+pub mod eval;
 pub mod move_gen;
+pub mod notation;
+pub mod outcome;
+pub mod perft;
+pub mod pgn;
+pub mod retrograde;
+pub mod search;
+pub mod tablebase;
 
 #[derive(Debug, Default, Clone)]
 pub struct Chess {
 	pub board: board::Board,
 	move_gen: Arc<move_gen::MoveGen>,
 	history: history::History,
+	eval_params: Arc<eval::EvalParams>,
+	tt: Arc<transposition::TranspositionTable<transposition::SearchData>>,
+	tablebase: Option<Arc<tablebase::Tablebase>>,
 }
 
-impl From<&str> for Chess {
-	fn from(fen: &str) -> Self {
-		let board = board::Board::from(fen);
+impl TryFrom<&str> for Chess {
+	type Error = board::FenError;
+
+	fn try_from(fen: &str) -> Result<Self, Self::Error> {
+		let board = board::Board::from_fen(fen)?;
 		let move_gen = move_gen::MoveGen::default();
 		let history = history::History::default();
 
-		Self {
+		Ok(Self {
 			board,
 			move_gen: Arc::new(move_gen),
 			history,
-		}
+			eval_params: Arc::default(),
+			tt: Arc::default(),
+			tablebase: None,
+		})
+	}
+}
+
+impl FromStr for Chess {
+	type Err = board::FenError;
+
+	fn from_str(fen: &str) -> Result<Self, Self::Err> {
+		Self::try_from(fen)
 	}
 }
 
@@ -52,4 +78,57 @@ impl Chess {
 
 		list
 	}
+
+	#[inline(always)]
+	pub fn generate_legal_moves(&self) -> MoveList {
+		let mut list = MoveList::default();
+
+		self.move_gen.legal(&self.board, &mut list);
+
+		list
+	}
+
+	#[inline(always)]
+	pub fn in_check(&self) -> bool {
+		let color = self.board.color;
+		let king = BitboardUtils::lsb(self.board.pieces[color][PieceUtils::KING]);
+
+		self.move_gen.square_attacked(&self.board, color ^ 1, king)
+	}
+
+	/// A copy of the current position without the game history.
+	pub(crate) fn position(&self) -> Self {
+		Self {
+			board: self.board.clone(),
+			move_gen: self.move_gen.clone(),
+			history: history::History::default(),
+			eval_params: self.eval_params.clone(),
+			tt: self.tt.clone(),
+			tablebase: self.tablebase.clone(),
+		}
+	}
+
+	/// Sets up a position from a FEN, keeping the Zobrist seed of the current board.
+	pub fn set_position(&mut self, fen: &str) -> Result<(), board::FenError> {
+		self.board = board::Board::from_fen_with_seed(fen, self.board.zobrist_seed())?;
+		self.history.clear();
+
+		Ok(())
+	}
+
+	/// Rehashes the game with the Zobrist keys generated from `seed`, replaying the history so
+	/// repetitions are still detected. The hash table is cleared, as its entries were stored under
+	/// the old keys.
+	pub fn set_zobrist_seed(&mut self, seed: u64) {
+		let moves = self
+			.history
+			.iter()
+			.map(|state| state.move_made)
+			.collect::<Vec<_>>();
+
+		moves.iter().for_each(|_| self.undo_move());
+		self.board.set_zobrist_seed(seed);
+		moves.into_iter().for_each(|m| self.make_move(m));
+		self.clear_hash();
+	}
 }
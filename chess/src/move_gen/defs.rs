@@ -5,6 +5,7 @@ pub type PieceMagics = [Magic; SquareUtils::SIZE];
 pub type PieceMoves = [Bitboard; SquareUtils::SIZE];
 pub type BlockerTable = Vec<Bitboard>;
 pub type AttackTable = Vec<Bitboard>;
+pub type SquarePairs = [[Bitboard; SquareUtils::SIZE]; SquareUtils::SIZE];
 
 pub const ROOK_TABLE_SIZE: usize = 102_400;
 pub const BISHOP_TABLE_SIZE: usize = 5_248;
@@ -0,0 +1,28 @@
+use super::MoveGen;
+use crate::board::{bitboard::BitboardUtils, piece::PieceUtils, Bitboard, Color, Piece, Square};
+
+impl MoveGen {
+	/// Squares attacked by `piece` of `color` standing on `square`, with sliders stopped by
+	/// `occupancy`.
+	#[inline(always)]
+	pub fn attacks(
+		&self,
+		piece: Piece,
+		color: Color,
+		square: Square,
+		occupancy: Bitboard,
+	) -> Bitboard {
+		match piece {
+			PieceUtils::PAWN => self.pawns[color][square],
+			PieceUtils::KNIGHT => self.knight[square],
+			PieceUtils::BISHOP => self.bishops[self.bishop_magics[square].index(occupancy)],
+			PieceUtils::ROOK => self.rooks[self.rook_magics[square].index(occupancy)],
+			PieceUtils::QUEEN => {
+				self.bishops[self.bishop_magics[square].index(occupancy)]
+					| self.rooks[self.rook_magics[square].index(occupancy)]
+			}
+			PieceUtils::KING => self.king[square],
+			_ => BitboardUtils::EMPTY,
+		}
+	}
+}
@@ -18,6 +18,8 @@ impl Default for MoveGen {
 			bishop_magics: [Magic::default(); SquareUtils::SIZE],
 			knight: [BitboardUtils::EMPTY; SquareUtils::SIZE],
 			pawns: [[BitboardUtils::EMPTY; SquareUtils::SIZE]; ColorUtils::SIZE],
+			between: Box::new([[BitboardUtils::EMPTY; SquareUtils::SIZE]; SquareUtils::SIZE]),
+			line: Box::new([[BitboardUtils::EMPTY; SquareUtils::SIZE]; SquareUtils::SIZE]),
 		};
 
 		move_gen.init_king();
@@ -25,6 +27,7 @@ impl Default for MoveGen {
 		move_gen.init_magic(PieceUtils::BISHOP);
 		move_gen.init_knight();
 		move_gen.init_pawn();
+		move_gen.init_lines();
 
 		move_gen
 	}
@@ -108,6 +111,36 @@ impl MoveGen {
 		}
 	}
 
+	fn init_lines(&mut self) {
+		for from in SquareUtils::RANGE {
+			for to in SquareUtils::RANGE {
+				for piece in [PieceUtils::BISHOP, PieceUtils::ROOK] {
+					let from_attacks =
+						self.attacks(piece, ColorUtils::WHITE, from, BitboardUtils::EMPTY);
+
+					if from == to || !BitboardUtils::occupied(from_attacks, to) {
+						continue;
+					}
+
+					let to_attacks =
+						self.attacks(piece, ColorUtils::WHITE, to, BitboardUtils::EMPTY);
+
+					self.line[from][to] = (from_attacks & to_attacks)
+						| BitboardUtils::SQUARES[from]
+						| BitboardUtils::SQUARES[to];
+					self.between[from][to] =
+						self.attacks(piece, ColorUtils::WHITE, from, BitboardUtils::SQUARES[to])
+							& self.attacks(
+								piece,
+								ColorUtils::WHITE,
+								to,
+								BitboardUtils::SQUARES[from],
+							);
+				}
+			}
+		}
+	}
+
 	// Marcel Vanthoor
 	// https://github.com/mvanthoor/rustic
 	fn init_magic(&mut self, piece: Piece) {
@@ -0,0 +1,335 @@
+use super::MoveGen;
+use crate::{
+	board::{
+		bitboard::BitboardUtils, castle_right::CastleRightUtils, color::ColorUtils,
+		file_rank::RankUtils, piece::PieceUtils, square::SquareUtils, Bitboard, Board, CastleRight,
+		Color, Square,
+	},
+	move_list::MoveList,
+};
+
+// Right, color, king origin, king destination, squares that must be empty, squares the king
+// crosses that must not be attacked.
+const CASTLES: [(CastleRight, Color, Square, Square, Bitboard, [Square; 2]); 4] = [
+	(
+		CastleRightUtils::WHITE_KING,
+		ColorUtils::WHITE,
+		SquareUtils::E1,
+		SquareUtils::G1,
+		BitboardUtils::SQUARES[SquareUtils::F1] | BitboardUtils::SQUARES[SquareUtils::G1],
+		[SquareUtils::F1, SquareUtils::G1],
+	),
+	(
+		CastleRightUtils::WHITE_QUEEN,
+		ColorUtils::WHITE,
+		SquareUtils::E1,
+		SquareUtils::C1,
+		BitboardUtils::SQUARES[SquareUtils::D1]
+			| BitboardUtils::SQUARES[SquareUtils::C1]
+			| BitboardUtils::SQUARES[SquareUtils::B1],
+		[SquareUtils::D1, SquareUtils::C1],
+	),
+	(
+		CastleRightUtils::BLACK_KING,
+		ColorUtils::BLACK,
+		SquareUtils::E8,
+		SquareUtils::G8,
+		BitboardUtils::SQUARES[SquareUtils::F8] | BitboardUtils::SQUARES[SquareUtils::G8],
+		[SquareUtils::F8, SquareUtils::G8],
+	),
+	(
+		CastleRightUtils::BLACK_QUEEN,
+		ColorUtils::BLACK,
+		SquareUtils::E8,
+		SquareUtils::C8,
+		BitboardUtils::SQUARES[SquareUtils::D8]
+			| BitboardUtils::SQUARES[SquareUtils::C8]
+			| BitboardUtils::SQUARES[SquareUtils::B8],
+		[SquareUtils::D8, SquareUtils::C8],
+	),
+];
+
+impl MoveGen {
+	/// Generates only legal moves, using the checkers and pinned pieces of the side to move to
+	/// restrict every piece's destinations up front.
+	pub fn legal(&self, board: &Board, list: &mut MoveList) {
+		let color = board.color;
+		let king = BitboardUtils::lsb(board.pieces[color][PieceUtils::KING]);
+		let checkers = self.attackers(board, king, board.occupancy, color ^ 1);
+
+		self.legal_king(board, king, list);
+
+		if checkers.count_ones() > 1 {
+			return;
+		}
+
+		let evasion = match checkers {
+			BitboardUtils::EMPTY => !BitboardUtils::EMPTY,
+			_ => checkers | self.between[king][BitboardUtils::lsb(checkers)],
+		};
+
+		let pinned = self.pinned(board, king);
+
+		for piece in [
+			PieceUtils::QUEEN,
+			PieceUtils::ROOK,
+			PieceUtils::BISHOP,
+			PieceUtils::KNIGHT,
+		] {
+			let mut pieces = board.pieces[color][piece];
+
+			while pieces > 0 {
+				let from = BitboardUtils::pop_lsb(&mut pieces);
+				let mut moves =
+					self.attacks(piece, color, from, board.occupancy) & !board.ally() & evasion;
+
+				if BitboardUtils::occupied(pinned, from) {
+					moves &= self.line[king][from];
+				}
+
+				self.add_move(board, piece, from, moves, list);
+			}
+		}
+
+		self.legal_pawns(board, king, evasion, pinned, list);
+
+		if checkers == BitboardUtils::EMPTY {
+			self.legal_castling(board, list);
+		}
+	}
+
+	/// Pieces of `color` attacking `square`, with sliders stopped by `occupancy`.
+	#[inline(always)]
+	pub fn attackers(
+		&self,
+		board: &Board,
+		square: Square,
+		occupancy: Bitboard,
+		color: Color,
+	) -> Bitboard {
+		let pieces = &board.pieces[color];
+
+		let rook = self.attacks(PieceUtils::ROOK, color, square, occupancy);
+		let bishop = self.attacks(PieceUtils::BISHOP, color, square, occupancy);
+
+		(self.king[square] & pieces[PieceUtils::KING])
+			| (self.knight[square] & pieces[PieceUtils::KNIGHT])
+			| (self.pawns[color ^ 1][square] & pieces[PieceUtils::PAWN])
+			| (rook & (pieces[PieceUtils::ROOK] | pieces[PieceUtils::QUEEN]))
+			| (bishop & (pieces[PieceUtils::BISHOP] | pieces[PieceUtils::QUEEN]))
+	}
+
+	/// Pieces of the side to move that shield their king from an enemy slider.
+	#[inline(always)]
+	pub fn pinned(&self, board: &Board, king: Square) -> Bitboard {
+		let opponent = board.color ^ 1;
+		let enemy = &board.pieces[opponent];
+		let enemy_occupancy = board.occupancy_color[opponent];
+
+		let mut snipers = (self.attacks(PieceUtils::ROOK, opponent, king, enemy_occupancy)
+			& (enemy[PieceUtils::ROOK] | enemy[PieceUtils::QUEEN]))
+			| (self.attacks(PieceUtils::BISHOP, opponent, king, enemy_occupancy)
+				& (enemy[PieceUtils::BISHOP] | enemy[PieceUtils::QUEEN]));
+
+		let mut pinned = BitboardUtils::EMPTY;
+
+		while snipers > 0 {
+			let sniper = BitboardUtils::pop_lsb(&mut snipers);
+			let blockers = self.between[king][sniper] & board.occupancy;
+
+			if blockers.count_ones() == 1 && blockers & board.ally() > 0 {
+				pinned |= blockers;
+			}
+		}
+
+		pinned
+	}
+
+	#[inline(always)]
+	fn legal_king(&self, board: &Board, king: Square, list: &mut MoveList) {
+		let opponent = board.color ^ 1;
+		let occupancy = board.occupancy & !BitboardUtils::SQUARES[king];
+
+		let mut targets = self.king[king] & !board.ally();
+		let mut moves = BitboardUtils::EMPTY;
+
+		while targets > 0 {
+			let to = BitboardUtils::pop_lsb(&mut targets);
+
+			if self.attackers(board, to, occupancy, opponent) == BitboardUtils::EMPTY {
+				moves |= BitboardUtils::SQUARES[to];
+			}
+		}
+
+		self.add_move(board, PieceUtils::KING, king, moves, list);
+	}
+
+	#[inline(always)]
+	fn legal_pawns(
+		&self,
+		board: &Board,
+		king: Square,
+		evasion: Bitboard,
+		pinned: Bitboard,
+		list: &mut MoveList,
+	) {
+		let color = board.color;
+		let empty = !board.occupancy;
+
+		let (forward, third) = match color {
+			ColorUtils::WHITE => (8, RankUtils::R3),
+			_ => (-8, RankUtils::R6),
+		};
+
+		let mut pieces = board.pieces[color][PieceUtils::PAWN];
+
+		while pieces > 0 {
+			let from = BitboardUtils::pop_lsb(&mut pieces);
+			let to = (from as isize + forward) as Square;
+
+			let one_step = empty & BitboardUtils::SQUARES[to];
+			let two_step = match one_step & BitboardUtils::RANKS[third] {
+				BitboardUtils::EMPTY => BitboardUtils::EMPTY,
+				_ => empty & BitboardUtils::SQUARES[(to as isize + forward) as Square],
+			};
+
+			let attacks = self.pawns[color][from];
+			let mut moves = (one_step | two_step | (attacks & board.enemy())) & evasion;
+
+			if BitboardUtils::occupied(pinned, from) {
+				moves &= self.line[king][from];
+			}
+
+			if let Some(en_passant) = board.en_passant {
+				if BitboardUtils::occupied(attacks, en_passant)
+					&& self.en_passant_legal(board, king, from, en_passant)
+				{
+					moves |= BitboardUtils::SQUARES[en_passant];
+				}
+			}
+
+			self.add_move(board, PieceUtils::PAWN, from, moves, list);
+		}
+	}
+
+	/// En passant removes two pieces at once, so it is verified by replaying the capture on the
+	/// occupancy instead of relying on the pin and evasion masks.
+	#[inline(always)]
+	fn en_passant_legal(&self, board: &Board, king: Square, from: Square, to: Square) -> bool {
+		let captured = BitboardUtils::SQUARES[to ^ 8];
+		let occupancy = (board.occupancy & !BitboardUtils::SQUARES[from] & !captured)
+			| BitboardUtils::SQUARES[to];
+
+		self.attackers(board, king, occupancy, board.color ^ 1) & !captured == BitboardUtils::EMPTY
+	}
+
+	#[inline(always)]
+	fn legal_castling(&self, board: &Board, list: &mut MoveList) {
+		let color = board.color;
+
+		for (right, castle_color, from, to, blockers, path) in CASTLES {
+			if castle_color != color
+				|| board.castle_rights & right == 0
+				|| board.occupancy & blockers > 0
+				|| path
+					.iter()
+					.any(|square| self.square_attacked(board, color ^ 1, *square))
+			{
+				continue;
+			}
+
+			self.add_move(
+				board,
+				PieceUtils::KING,
+				from,
+				BitboardUtils::SQUARES[to],
+				list,
+			);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::Chess;
+
+	/// Leaf counts of the standard perft positions, from depth 1.
+	const POSITIONS: [(&str, &[usize]); 6] = [
+		(
+			"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+			&[20, 400, 8902, 197281],
+		),
+		(
+			"r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+			&[48, 2039, 97862],
+		),
+		(
+			"8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+			&[14, 191, 2812, 43238],
+		),
+		(
+			"r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+			&[6, 264, 9467],
+		),
+		(
+			"rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+			&[44, 1486, 62379],
+		),
+		(
+			"r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+			&[46, 2079, 89890],
+		),
+	];
+
+	fn perft(chess: &mut Chess, depth: usize) -> usize {
+		let moves = chess.generate_legal_moves();
+
+		if depth == 1 {
+			return moves.len();
+		}
+
+		moves
+			.iter()
+			.map(|&m| {
+				chess.make_move(m);
+				let nodes = perft(chess, depth - 1);
+				chess.undo_move();
+
+				nodes
+			})
+			.sum()
+	}
+
+	#[test]
+	fn perft_positions() {
+		for (fen, counts) in POSITIONS {
+			let mut chess = Chess::try_from(fen).unwrap();
+
+			for (depth, &count) in counts.iter().enumerate() {
+				assert_eq!(
+					perft(&mut chess, depth + 1),
+					count,
+					"{fen} depth {}",
+					depth + 1
+				);
+			}
+		}
+	}
+
+	/// En passant captures that would expose the king along the rank, and queenside castling
+	/// through an attacked square.
+	#[test]
+	fn pinned_pieces_and_en_passant() {
+		let cases = [
+			("8/8/8/KPp4r/8/8/8/7k w - c6 0 2", 4),
+			("8/8/8/8/k2Pp2Q/8/8/4K3 b - d3 0 1", 6),
+			("4k3/8/8/8/8/8/3r4/R3K2R w KQ - 0 1", 22),
+		];
+
+		for (fen, count) in cases {
+			let mut chess = Chess::try_from(fen).unwrap();
+
+			assert_eq!(perft(&mut chess, 1), count, "{fen}");
+		}
+	}
+}
@@ -1,5 +1,7 @@
+mod attacks;
 mod check;
 mod init;
+mod legal;
 mod mask;
 
 use super::prelude::*;
@@ -24,6 +24,8 @@ pub struct MoveGen {
 	bishop_magics: PieceMagics,
 	knight: PieceMoves,
 	pawns: [PieceMoves; ColorUtils::SIZE],
+	between: Box<SquarePairs>,
+	line: Box<SquarePairs>,
 }
 
 impl MoveGen {
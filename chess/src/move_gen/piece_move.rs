@@ -44,9 +44,14 @@ impl fmt::Display for Move {
 		let from = self.from();
 		let to = self.to();
 
+		let promoted = match self.promoted() {
+			PieceUtils::NONE => String::new(),
+			piece => PieceUtils::to_string(piece, ColorUtils::BLACK),
+		};
+
 		write!(
 			f,
-			"{}{}",
+			"{}{}{promoted}",
 			SquareUtils::to_string(from),
 			SquareUtils::to_string(to)
 		)
@@ -51,7 +51,7 @@ impl MoveList {
 			.collect()
 	}
 
-	pub fn iter(&self) -> std::slice::Iter<Move> {
+	pub fn iter(&self) -> std::slice::Iter<'_, Move> {
 		self.list[..self.count].iter()
 	}
 }
@@ -0,0 +1,26 @@
+mod san;
+mod uci;
+
+use std::{error::Error, fmt};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveParseError {
+	/// The text is not written in the expected notation.
+	InvalidSyntax(String),
+	/// The text is well formed but no legal move in the position matches it.
+	IllegalMove(String),
+	/// The text matches more than one legal move in the position.
+	AmbiguousMove(String),
+}
+
+impl fmt::Display for MoveParseError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidSyntax(text) => write!(f, "invalid move syntax: '{text}'"),
+			Self::IllegalMove(text) => write!(f, "illegal move: '{text}'"),
+			Self::AmbiguousMove(text) => write!(f, "ambiguous move: '{text}'"),
+		}
+	}
+}
+
+impl Error for MoveParseError {}
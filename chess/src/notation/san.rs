@@ -0,0 +1,281 @@
+use super::MoveParseError;
+use crate::{
+	board::{
+		color::ColorUtils,
+		file_rank::{FileUtils, RankUtils},
+		piece::PieceUtils,
+		square::SquareUtils,
+		Piece,
+	},
+	move_gen::Move,
+	Chess,
+};
+
+impl Chess {
+	/// Formats a legal move in Standard Algebraic Notation, such as `Nbd2`, `exd6`, `O-O-O`,
+	/// `e8=Q+` or `Qxf7#`.
+	pub fn to_san(&self, m: Move) -> String {
+		let piece = m.piece();
+		let from = m.from();
+		let to = m.to();
+
+		let mut san = String::new();
+
+		if m.castling() {
+			san += match SquareUtils::location(to).0 {
+				FileUtils::G => "O-O",
+				_ => "O-O-O",
+			};
+		} else {
+			let capture = m.captured() != PieceUtils::NONE || m.en_passant();
+			let (from_file, from_rank) = SquareUtils::location(from);
+
+			if piece == PieceUtils::PAWN {
+				if capture {
+					san.push(FileUtils::to_char(from_file));
+				}
+			} else {
+				san += &PieceUtils::to_string(piece, ColorUtils::WHITE);
+
+				let rivals = self
+					.generate_legal_moves()
+					.iter()
+					.filter(|other| {
+						other.piece() == piece && other.to() == to && other.from() != from
+					})
+					.map(|other| SquareUtils::location(other.from()))
+					.collect::<Vec<_>>();
+
+				if !rivals.is_empty() {
+					let shares_file = rivals.iter().any(|(file, _)| *file == from_file);
+					let shares_rank = rivals.iter().any(|(_, rank)| *rank == from_rank);
+
+					if !shares_file {
+						san.push(FileUtils::to_char(from_file));
+					} else if !shares_rank {
+						san.push(RankUtils::to_char(from_rank));
+					} else {
+						san += &SquareUtils::to_string(from);
+					}
+				}
+			}
+
+			if capture {
+				san.push('x');
+			}
+
+			san += &SquareUtils::to_string(to);
+
+			if m.promoted() != PieceUtils::NONE {
+				san.push('=');
+				san += &PieceUtils::to_string(m.promoted(), ColorUtils::WHITE);
+			}
+		}
+
+		let mut position = self.position();
+		position.make_move(m);
+
+		if position.in_check() {
+			san.push(match position.has_legal_move() {
+				true => '+',
+				false => '#',
+			});
+		}
+
+		san
+	}
+
+	/// Resolves Standard Algebraic Notation to a legal move. Check and annotation suffixes,
+	/// `e.p.`, `0-0` castling and missing `x` or `=` are tolerated.
+	pub fn parse_san(&self, text: &str) -> Result<Move, MoveParseError> {
+		let invalid = || MoveParseError::InvalidSyntax(text.to_string());
+
+		let san = text
+			.trim()
+			.trim_end_matches("e.p.")
+			.trim_end()
+			.trim_end_matches(['+', '#', '!', '?']);
+
+		let list = self.generate_legal_moves();
+
+		let castle_file = match san {
+			"O-O" | "0-0" => Some(FileUtils::G),
+			"O-O-O" | "0-0-0" => Some(FileUtils::C),
+			_ => None,
+		};
+
+		if let Some(file) = castle_file {
+			return list
+				.iter()
+				.find(|m| m.castling() && SquareUtils::location(m.to()).0 == file)
+				.copied()
+				.ok_or_else(|| MoveParseError::IllegalMove(text.to_string()));
+		}
+
+		let mut chars = san
+			.chars()
+			.filter(|ch| !matches!(ch, 'x' | ':' | '-' | '='))
+			.collect::<Vec<char>>();
+
+		let piece = match chars.first() {
+			Some(ch) if ch.is_ascii_uppercase() => {
+				let piece = parse_piece(*ch).ok_or_else(invalid)?;
+				chars.remove(0);
+				piece
+			}
+			Some(_) => PieceUtils::PAWN,
+			None => return Err(invalid()),
+		};
+
+		let promoted = match chars.last() {
+			Some(ch)
+				if piece == PieceUtils::PAWN && ch.is_ascii_alphabetic() && chars.len() > 2 =>
+			{
+				let promoted = parse_piece(ch.to_ascii_uppercase())
+					.filter(|piece| PieceUtils::PROMOTIONS.contains(piece))
+					.ok_or_else(invalid)?;
+				chars.pop();
+				promoted
+			}
+			_ => PieceUtils::NONE,
+		};
+
+		if chars.len() < 2 {
+			return Err(invalid());
+		}
+
+		let destination = chars
+			.split_off(chars.len() - 2)
+			.into_iter()
+			.collect::<String>();
+		let to = SquareUtils::try_parse(&destination).ok_or_else(invalid)?;
+
+		let mut from_file = None;
+		let mut from_rank = None;
+
+		for ch in chars {
+			if let Some(file) = FileUtils::try_from_char(ch) {
+				from_file = Some(file);
+			} else if let Some(rank) = RankUtils::try_from_char(ch) {
+				from_rank = Some(rank);
+			} else {
+				return Err(invalid());
+			}
+		}
+
+		let mut candidates = list.iter().filter(|m| {
+			let (file, rank) = SquareUtils::location(m.from());
+
+			m.piece() == piece
+				&& m.to() == to
+				&& m.promoted() == promoted
+				&& !m.castling()
+				&& from_file.is_none_or(|from_file| from_file == file)
+				&& from_rank.is_none_or(|from_rank| from_rank == rank)
+		});
+
+		match (candidates.next(), candidates.next()) {
+			(Some(m), None) => Ok(*m),
+			(Some(_), Some(_)) => Err(MoveParseError::AmbiguousMove(text.to_string())),
+			(None, _) => Err(MoveParseError::IllegalMove(text.to_string())),
+		}
+	}
+}
+
+fn parse_piece(ch: char) -> Option<Piece> {
+	match ch {
+		'N' | 'B' | 'R' | 'Q' | 'K' => PieceUtils::try_parse(ch),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn to_san_and_back() {
+		let cases = [
+			("4k3/8/8/8/8/8/8/1N1K1N2 w - - 0 1", "b1d2", "Nbd2"),
+			("4k3/8/8/N7/8/8/8/N3K3 w - - 0 1", "a5b3", "N5b3"),
+			("4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1", "a1b2", "Qa1b2"),
+			("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7b8q", "b8=Q+"),
+			("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7b8n", "b8=N"),
+			("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6", "exd6"),
+			("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "e1g1", "O-O"),
+			("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "e1c1", "O-O-O"),
+			(
+				"rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq g3 0 2",
+				"d8h4",
+				"Qh4#",
+			),
+		];
+
+		for (fen, uci, san) in cases {
+			let chess = Chess::try_from(fen).unwrap();
+			let m = chess.parse_uci_move(uci).unwrap();
+
+			assert_eq!(chess.to_san(m), san, "{fen}");
+			assert_eq!(chess.parse_san(san), Ok(m), "{fen}");
+		}
+	}
+
+	#[test]
+	fn parse_lenient_san() {
+		let cases = [
+			("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "0-0-0", "e1c1"),
+			("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "exd6 e.p.", "e5d6"),
+			("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b8Q", "b7b8q"),
+			("4k3/8/8/8/8/8/8/1N1K1N2 w - - 0 1", "Nb1-d2!?", "b1d2"),
+		];
+
+		for (fen, san, uci) in cases {
+			let chess = Chess::try_from(fen).unwrap();
+
+			assert_eq!(chess.parse_san(san), chess.parse_uci_move(uci), "{san}");
+		}
+
+		let chess = Chess::try_from("4k3/8/8/8/8/8/8/1N1K1N2 w - - 0 1").unwrap();
+
+		assert!(matches!(
+			chess.parse_san("Nd2"),
+			Err(MoveParseError::AmbiguousMove(_))
+		));
+		assert!(matches!(
+			chess.parse_san("Nd3"),
+			Err(MoveParseError::IllegalMove(_))
+		));
+	}
+
+	/// Every move two plies deep in the standard perft positions survives a round trip.
+	#[test]
+	fn round_trip() {
+		let fens = [
+			"r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+			"8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+			"r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+			"rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+		];
+
+		for fen in fens {
+			let mut chess = Chess::try_from(fen).unwrap();
+
+			for &first in chess.generate_legal_moves().iter() {
+				chess.make_move(first);
+
+				for &m in chess.generate_legal_moves().iter() {
+					let san = chess.to_san(m);
+
+					assert_eq!(
+						chess.parse_san(&san),
+						Ok(m),
+						"{san} in {}",
+						chess.board.to_fen()
+					);
+				}
+
+				chess.undo_move();
+			}
+		}
+	}
+}
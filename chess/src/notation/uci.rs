@@ -0,0 +1,35 @@
+use super::MoveParseError;
+use crate::{
+	board::{piece::PieceUtils, square::SquareUtils},
+	move_gen::Move,
+	Chess,
+};
+
+impl Chess {
+	/// Resolves long algebraic notation such as `e2e4`, `e7e8q` or `e1g1` to a legal move.
+	pub fn parse_uci_move(&self, text: &str) -> Result<Move, MoveParseError> {
+		let invalid = || MoveParseError::InvalidSyntax(text.to_string());
+
+		if !text.is_ascii() || !(4..=5).contains(&text.len()) {
+			return Err(invalid());
+		}
+
+		let from = SquareUtils::try_parse(&text[0..2]).ok_or_else(invalid)?;
+		let to = SquareUtils::try_parse(&text[2..4]).ok_or_else(invalid)?;
+
+		let promoted = match text[4..].chars().next() {
+			Some(ch) if ch.is_ascii_lowercase() => match PieceUtils::try_parse(ch) {
+				Some(piece) if PieceUtils::PROMOTIONS.contains(&piece) => piece,
+				_ => return Err(invalid()),
+			},
+			Some(_) => return Err(invalid()),
+			None => PieceUtils::NONE,
+		};
+
+		self.generate_legal_moves()
+			.iter()
+			.find(|m| m.from() == from && m.to() == to && m.promoted() == promoted)
+			.copied()
+			.ok_or_else(|| MoveParseError::IllegalMove(text.to_string()))
+	}
+}
@@ -0,0 +1,216 @@
+use crate::{
+	board::{bitboard::BitboardUtils, color::ColorUtils, piece::PieceUtils, Color},
+	Chess,
+};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+	Checkmate {
+		winner: Color,
+	},
+	Stalemate,
+	InsufficientMaterial,
+	/// The same position occurred five times, ending the game automatically.
+	FivefoldRepetition,
+	/// Seventy-five moves by each side without a capture or pawn move, ending the game automatically.
+	SeventyFiveMoveRule,
+	/// The same position occurred three times, a draw either side can claim.
+	ThreefoldRepetition,
+	/// Fifty moves by each side without a capture or pawn move, a draw either side can claim.
+	FiftyMoveRule,
+}
+
+impl Outcome {
+	pub fn winner(&self) -> Option<Color> {
+		match self {
+			Self::Checkmate { winner } => Some(*winner),
+			_ => None,
+		}
+	}
+
+	pub fn is_draw(&self) -> bool {
+		self.winner().is_none()
+	}
+}
+
+impl fmt::Display for Outcome {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Checkmate { winner } => match *winner {
+				ColorUtils::WHITE => write!(f, "1-0 (checkmate)"),
+				_ => write!(f, "0-1 (checkmate)"),
+			},
+			Self::Stalemate => write!(f, "1/2-1/2 (stalemate)"),
+			Self::InsufficientMaterial => write!(f, "1/2-1/2 (insufficient material)"),
+			Self::FivefoldRepetition => write!(f, "1/2-1/2 (fivefold repetition)"),
+			Self::SeventyFiveMoveRule => write!(f, "1/2-1/2 (seventy-five move rule)"),
+			Self::ThreefoldRepetition => write!(f, "1/2-1/2 (threefold repetition)"),
+			Self::FiftyMoveRule => write!(f, "1/2-1/2 (fifty move rule)"),
+		}
+	}
+}
+
+impl Chess {
+	/// Returns how the game ended, or `None` while it is still in progress.
+	pub fn outcome(&self) -> Option<Outcome> {
+		if !self.has_legal_move() {
+			return Some(match self.in_check() {
+				true => Outcome::Checkmate {
+					winner: self.board.color ^ 1,
+				},
+				false => Outcome::Stalemate,
+			});
+		}
+
+		let repetitions = self.repetitions();
+		let halfmove_clock = self.board.halfmove_clock;
+
+		if self.insufficient_material() {
+			Some(Outcome::InsufficientMaterial)
+		} else if repetitions >= 5 {
+			Some(Outcome::FivefoldRepetition)
+		} else if halfmove_clock >= 150 {
+			Some(Outcome::SeventyFiveMoveRule)
+		} else if repetitions >= 3 {
+			Some(Outcome::ThreefoldRepetition)
+		} else if halfmove_clock >= 100 {
+			Some(Outcome::FiftyMoveRule)
+		} else {
+			None
+		}
+	}
+
+	pub fn has_legal_move(&self) -> bool {
+		self.generate_legal_moves().len() > 0
+	}
+
+	/// Number of times the current position occurred, counting itself.
+	pub fn repetitions(&self) -> usize {
+		let reversible = (self.board.halfmove_clock as usize).min(self.history.len());
+
+		1 + self.history[self.history.len() - reversible..]
+			.iter()
+			.filter(|state| state.hash == self.board.hash)
+			.count()
+	}
+
+	/// Whether neither side can possibly deliver checkmate.
+	pub fn insufficient_material(&self) -> bool {
+		let pieces = &self.board.pieces;
+
+		for color in ColorUtils::RANGE {
+			if pieces[color][PieceUtils::PAWN]
+				| pieces[color][PieceUtils::ROOK]
+				| pieces[color][PieceUtils::QUEEN]
+				> 0
+			{
+				return false;
+			}
+		}
+
+		let knights = pieces[ColorUtils::WHITE][PieceUtils::KNIGHT]
+			| pieces[ColorUtils::BLACK][PieceUtils::KNIGHT];
+		let bishops = pieces[ColorUtils::WHITE][PieceUtils::BISHOP]
+			| pieces[ColorUtils::BLACK][PieceUtils::BISHOP];
+
+		if (knights | bishops).count_ones() <= 1 {
+			return true;
+		}
+
+		knights == 0
+			&& (bishops & BitboardUtils::LIGHT_SQUARES == 0
+				|| bishops & BitboardUtils::DARK_SQUARES == 0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn play(chess: &mut Chess, moves: &str) {
+		for text in moves.split_whitespace() {
+			let m = chess.parse_uci_move(text).unwrap();
+			chess.make_move(m);
+		}
+	}
+
+	#[test]
+	fn threefold_repetition() {
+		let mut chess = Chess::default();
+		let cycle = "g1f3 g8f6 f3g1 f6g8";
+
+		play(&mut chess, cycle);
+		assert_eq!(chess.repetitions(), 2);
+		assert_eq!(chess.outcome(), None);
+
+		play(&mut chess, cycle);
+		assert_eq!(chess.repetitions(), 3);
+		assert_eq!(chess.outcome(), Some(Outcome::ThreefoldRepetition));
+
+		play(&mut chess, cycle);
+		play(&mut chess, cycle);
+		assert_eq!(chess.repetitions(), 5);
+		assert_eq!(chess.outcome(), Some(Outcome::FivefoldRepetition));
+	}
+
+	#[test]
+	fn lost_castle_rights_start_a_new_position() {
+		let mut chess =
+			Chess::try_from("r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1").unwrap();
+		let cycle = "a1b1 a8b8 b1a1 b8a8";
+
+		play(&mut chess, cycle);
+		assert_eq!(chess.repetitions(), 1);
+
+		play(&mut chess, cycle);
+		assert_eq!(chess.repetitions(), 2);
+		assert_eq!(chess.outcome(), None);
+
+		play(&mut chess, cycle);
+		assert_eq!(chess.repetitions(), 3);
+		assert_eq!(chess.outcome(), Some(Outcome::ThreefoldRepetition));
+	}
+
+	#[test]
+	fn repetitions_stop_at_irreversible_moves() {
+		let mut chess = Chess::default();
+
+		play(&mut chess, "g1f3 g8f6 f3g1 f6g8 e2e4 e7e5");
+		play(&mut chess, "g1f3 g8f6 f3g1 f6g8 g1f3 g8f6 f3g1 f6g8");
+
+		// The position after e7e5 counts too, since no pawn can take on e6.
+		assert_eq!(chess.repetitions(), 3);
+	}
+
+	#[test]
+	fn uncapturable_en_passant_squares_repeat() {
+		let mut chess = Chess::default();
+
+		// After e2e4 no black pawn can take on e3, so the position repeats the one after the
+		// knight moves. Once a pawn stands on d4, the en passant square makes it a new position.
+		play(&mut chess, "e2e4 g8f6 g1f3 f6g8 f3g1");
+		assert_eq!(chess.repetitions(), 2);
+
+		let mut chess = Chess::try_from("4k3/8/8/8/3p4/8/4P3/4K3 w - - 0 1").unwrap();
+
+		play(&mut chess, "e2e4 e8d8 e1d1 d8e8 d1e1");
+		assert_eq!(chess.repetitions(), 1);
+	}
+
+	#[test]
+	fn checkmate_and_stalemate() {
+		let mut chess = Chess::default();
+		play(&mut chess, "f2f3 e7e5 g2g4 d8h4");
+
+		assert_eq!(
+			chess.outcome(),
+			Some(Outcome::Checkmate {
+				winner: ColorUtils::BLACK
+			})
+		);
+
+		let chess = Chess::try_from("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+		assert_eq!(chess.outcome(), Some(Outcome::Stalemate));
+	}
+}
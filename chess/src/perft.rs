@@ -1,120 +1,521 @@
 use crate::{
-	transposition::{Depth as _, PerftData, TranspositionTable},
+	board::{bitboard::BitboardUtils, piece::PieceUtils},
+	move_gen::Move,
+	transposition::{PerftData, TableData as _, TranspositionTable},
 	Chess,
 };
 
 use std::{
-	sync::{Arc, RwLock},
+	ops::AddAssign,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		mpsc, Arc,
+	},
 	thread,
+	time::{Duration, Instant},
 };
 
+const SPLIT_PLY: u8 = 2;
+
+/// The result of a perft run.
+#[derive(Debug, Default, Clone)]
+pub struct PerftReport {
+	/// The node count below each legal root move, in generation order.
+	pub moves: Vec<(Move, usize)>,
+	pub nodes: usize,
+	pub elapsed: Duration,
+	/// The number of threads actually used, capped at the number of cores.
+	pub threads: usize,
+	pub tt: TtStats,
+}
+
+impl PerftReport {
+	pub fn nodes_per_second(&self) -> u64 {
+		(self.nodes as f64 / self.elapsed.as_secs_f64().max(1e-3)) as u64
+	}
+}
+
+/// Transposition table usage of a perft run, all zero when hashing is disabled.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TtStats {
+	pub probes: usize,
+	pub hits: usize,
+}
+
+impl TtStats {
+	pub fn hit_rate(&self) -> f64 {
+		match self.probes {
+			0 => 0.0,
+			probes => self.hits as f64 / probes as f64,
+		}
+	}
+}
+
+/// Counters for the nodes at one depth of a perft tree, as listed in standard perft tables.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PerftStats {
+	pub nodes: usize,
+	/// Captures, including en passant captures.
+	pub captures: usize,
+	pub en_passant: usize,
+	pub castles: usize,
+	pub promotions: usize,
+	pub checks: usize,
+	/// Checks not given by the piece that moved.
+	pub discovered_checks: usize,
+	pub double_checks: usize,
+	pub checkmates: usize,
+}
+
+impl AddAssign for PerftStats {
+	fn add_assign(&mut self, rhs: Self) {
+		self.nodes += rhs.nodes;
+		self.captures += rhs.captures;
+		self.en_passant += rhs.en_passant;
+		self.castles += rhs.castles;
+		self.promotions += rhs.promotions;
+		self.checks += rhs.checks;
+		self.discovered_checks += rhs.discovered_checks;
+		self.double_checks += rhs.double_checks;
+		self.checkmates += rhs.checkmates;
+	}
+}
+
 impl Chess {
-	#[inline(always)]
-	pub fn perft(
+	/// Counts the leaf nodes `depth` plies below the position, handing subtrees out to threads
+	/// and sharing a transposition table of `bytes` bytes when given.
+	pub fn perft(&mut self, depth: u8, num_threads: usize, bytes: Option<usize>) -> PerftReport {
+		self.perft_with_progress(depth, num_threads, bytes, |_, _| {})
+	}
+
+	/// Like `perft`, calling `progress` on this thread with each root move as soon as its count
+	/// is known.
+	pub fn perft_with_progress(
 		&mut self,
 		depth: u8,
-		mut num_threads: usize,
+		num_threads: usize,
 		bytes: Option<usize>,
-	) -> (usize, u128) {
+		mut progress: impl FnMut(Move, usize),
+	) -> PerftReport {
+		let num_threads = num_threads.clamp(1, num_cpus::get());
+
+		let mut report = PerftReport {
+			threads: num_threads,
+			..PerftReport::default()
+		};
+
 		if depth == 0 {
-			return (0, 0);
+			return report;
 		}
 
-		let num_cores = num_cpus::get();
+		let list = self.generate_legal_moves();
 
-		if num_threads >= num_cores {
-			println!("\x1b[33m\x1b[1mWarning:\x1b[0m Number of threads exceeds cores. Using all cores.\x1b[0m");
-			num_threads = num_cores;
+		// Work is handed out as subtrees below the first plies, so that threads finishing early
+		// take over the rest of a heavy root move instead of idling.
+		let split_ply = depth.clamp(1, SPLIT_PLY);
+		let mut tasks = Vec::new();
+
+		for (root, m) in list.iter().enumerate() {
+			self.make_move(*m);
+			self.perft_tasks(root, &mut vec![*m], split_ply - 1, &mut tasks);
+			self.undo_move();
 		}
 
-		num_threads = num_threads.max(1);
+		let mut remaining = vec![0; list.len()];
 
-		let list = self.generate_moves();
+		for (root, _) in &tasks {
+			remaining[*root] += 1;
+		}
 
-		let move_chunks = list.chunks(list.len().div_ceil(num_threads));
+		let tasks = Arc::new(tasks);
+		let next_task = Arc::new(AtomicUsize::new(0));
 
-		let tt_enabled = bytes.is_some();
+		let tt = bytes.map(|bytes| Arc::new(TranspositionTable::new(bytes)));
 
-		let tt = Arc::new(RwLock::new(match bytes {
-			Some(mb) => TranspositionTable::new(mb / num_threads),
-			None => TranspositionTable::default(),
-		}));
+		let start = Instant::now();
 
-		let start = std::time::Instant::now();
+		let (sender, receiver) = mpsc::channel();
 
-		let handles = move_chunks
-			.iter()
-			.map(|chunk| {
+		let handles = (0..num_threads)
+			.map(|_| {
 				let mut chess_clone = self.clone();
 
-				let chunk = chunk.clone();
-
+				let tasks = tasks.clone();
+				let next_task = next_task.clone();
 				let tt_clone = tt.clone();
+				let sender = sender.clone();
 
 				thread::spawn(move || {
-					let mut nodes = 0;
+					let mut tt_stats = TtStats::default();
 
-					for m in chunk {
-						if chess_clone.play_move(m) {
-							let move_nodes =
-								chess_clone.perft_driver(depth - 1, &tt_clone, tt_enabled);
-							chess_clone.undo_move();
+					while let Some((root, path)) =
+						tasks.get(next_task.fetch_add(1, Ordering::Relaxed))
+					{
+						for m in path {
+							chess_clone.make_move(*m);
+						}
+
+						let task_depth = depth - path.len() as u8;
+						let nodes = chess_clone.perft_driver(
+							task_depth,
+							tt_clone.as_deref(),
+							&mut tt_stats,
+						);
 
-							nodes += move_nodes;
-							println!("{m}: {move_nodes}");
+						for _ in path {
+							chess_clone.undo_move();
 						}
+
+						sender.send((*root, nodes)).unwrap();
 					}
 
-					nodes
+					tt_stats
 				})
 			})
 			.collect::<Vec<_>>();
 
-		let mut nodes = 0;
+		drop(sender);
+
+		let mut moves = list.iter().map(|m| (*m, 0)).collect::<Vec<_>>();
+
+		for (root, (m, move_nodes)) in moves.iter().enumerate() {
+			if remaining[root] == 0 {
+				progress(*m, *move_nodes);
+			}
+		}
+
+		for (root, nodes) in receiver {
+			let (m, move_nodes) = &mut moves[root];
+
+			*move_nodes += nodes;
+			remaining[root] -= 1;
+
+			if remaining[root] == 0 {
+				progress(*m, *move_nodes);
+			}
+		}
 
 		for handle in handles {
-			nodes += handle.join().unwrap()
+			let tt_stats = handle.join().unwrap();
+
+			report.tt.probes += tt_stats.probes;
+			report.tt.hits += tt_stats.hits;
 		}
 
-		(nodes, start.elapsed().as_millis())
+		report.moves = moves;
+		report.nodes = report.moves.iter().map(|(_, move_nodes)| move_nodes).sum();
+		report.elapsed = start.elapsed();
+
+		report
+	}
+
+	/// Collects the move sequences `plies` deeper than `path` as perft tasks of root move `root`.
+	fn perft_tasks(
+		&mut self,
+		root: usize,
+		path: &mut Vec<Move>,
+		plies: u8,
+		tasks: &mut Vec<(usize, Vec<Move>)>,
+	) {
+		if plies == 0 {
+			tasks.push((root, path.clone()));
+			return;
+		}
+
+		for m in self.generate_legal_moves().iter() {
+			self.make_move(*m);
+			path.push(*m);
+			self.perft_tasks(root, path, plies - 1, tasks);
+			path.pop();
+			self.undo_move();
+		}
 	}
 
 	#[inline(always)]
-	pub fn perft_driver(
+	fn perft_driver(
 		&mut self,
 		depth: u8,
-		tt: &Arc<RwLock<TranspositionTable<PerftData>>>,
-		tt_enabled: bool,
+		tt: Option<&TranspositionTable<PerftData>>,
+		tt_stats: &mut TtStats,
 	) -> usize {
 		if depth == 0 {
 			return 1;
 		}
 
-		if tt_enabled {
-			if let Some(data) = tt.read().unwrap().get(self.board.hash) {
+		if let Some(tt) = tt {
+			tt_stats.probes += 1;
+
+			if let Some(data) = tt.get(self.board.hash) {
 				if data.depth() == depth {
+					tt_stats.hits += 1;
 					return data.nodes();
 				}
 			}
 		}
 
-		let mut nodes = 0;
+		let list = self.generate_legal_moves();
 
-		let list = self.generate_moves();
+		if depth == 1 {
+			return list.len();
+		}
+
+		let mut nodes = 0;
 
 		for m in list.iter() {
-			if self.play_move(*m) {
-				nodes += self.perft_driver(depth - 1, tt, tt_enabled);
-				self.undo_move();
-			}
+			self.make_move(*m);
+			nodes += self.perft_driver(depth - 1, tt, tt_stats);
+			self.undo_move();
 		}
 
-		if tt_enabled {
-			tt.write()
-				.unwrap()
-				.insert(self.board.hash, PerftData::new(depth, nodes));
+		if let Some(tt) = tt {
+			tt.insert(self.board.hash, PerftData::new(depth, nodes));
 		}
 
 		nodes
 	}
+
+	/// Walks the whole tree without hashing and collects the statistics of every depth from 1
+	/// to `depth`, returning them with the elapsed time.
+	pub fn perft_stats(&mut self, depth: u8, num_threads: usize) -> (Vec<PerftStats>, Duration) {
+		let depth = depth as usize;
+
+		if depth == 0 {
+			return (Vec::new(), Duration::ZERO);
+		}
+
+		let num_threads = num_threads.clamp(1, num_cpus::get());
+
+		let start = Instant::now();
+
+		// The first plies are recorded here, and the subtrees below them handed out to threads
+		// like in `perft_with_progress`.
+		let split_ply = depth.min(SPLIT_PLY as usize);
+		let mut stats = vec![PerftStats::default(); depth];
+		let mut tasks = Vec::new();
+
+		self.perft_stats_tasks(&mut Vec::new(), &mut stats[..split_ply], &mut tasks);
+
+		let tasks = Arc::new(tasks);
+		let next_task = Arc::new(AtomicUsize::new(0));
+
+		let handles = (0..num_threads)
+			.map(|_| {
+				let mut chess_clone = self.clone();
+
+				let tasks = tasks.clone();
+				let next_task = next_task.clone();
+
+				thread::spawn(move || {
+					let mut stats = vec![PerftStats::default(); depth - split_ply];
+
+					while let Some(path) = tasks.get(next_task.fetch_add(1, Ordering::Relaxed)) {
+						for m in path {
+							chess_clone.make_move(*m);
+						}
+
+						chess_clone.perft_stats_driver(&mut stats);
+
+						for _ in path {
+							chess_clone.undo_move();
+						}
+					}
+
+					stats
+				})
+			})
+			.collect::<Vec<_>>();
+
+		for handle in handles {
+			for (total, thread_stats) in stats[split_ply..].iter_mut().zip(handle.join().unwrap()) {
+				*total += thread_stats;
+			}
+		}
+
+		(stats, start.elapsed())
+	}
+
+	/// Records the plies of `stats` like `perft_stats_driver`, collecting the move sequences
+	/// that reach below them as tasks.
+	fn perft_stats_tasks(
+		&mut self,
+		path: &mut Vec<Move>,
+		stats: &mut [PerftStats],
+		tasks: &mut Vec<Vec<Move>>,
+	) {
+		let Some((current, deeper)) = stats.split_first_mut() else {
+			tasks.push(path.clone());
+			return;
+		};
+
+		for m in self.generate_legal_moves().iter() {
+			self.make_move(*m);
+			self.record_stats(*m, current);
+			path.push(*m);
+			self.perft_stats_tasks(path, deeper, tasks);
+			path.pop();
+			self.undo_move();
+		}
+	}
+
+	/// Records the moves from this position in `stats[0]` and deeper plies in the rest.
+	fn perft_stats_driver(&mut self, stats: &mut [PerftStats]) {
+		let Some((current, deeper)) = stats.split_first_mut() else {
+			return;
+		};
+
+		let list = self.generate_legal_moves();
+
+		for m in list.iter() {
+			self.make_move(*m);
+			self.record_stats(*m, current);
+			self.perft_stats_driver(deeper);
+			self.undo_move();
+		}
+	}
+
+	/// Counts the node reached by `m`, which has just been played.
+	fn record_stats(&self, m: Move, stats: &mut PerftStats) {
+		let board = &self.board;
+
+		stats.nodes += 1;
+
+		if m.captured() != PieceUtils::NONE || m.en_passant() {
+			stats.captures += 1;
+		}
+
+		if m.en_passant() {
+			stats.en_passant += 1;
+		}
+
+		if m.castling() {
+			stats.castles += 1;
+		}
+
+		if m.promoted() != PieceUtils::NONE {
+			stats.promotions += 1;
+		}
+
+		let king = BitboardUtils::lsb(board.pieces[board.color][PieceUtils::KING]);
+		let checkers = self
+			.move_gen
+			.attackers(board, king, board.occupancy, board.color ^ 1);
+
+		if checkers == BitboardUtils::EMPTY {
+			return;
+		}
+
+		// The rook gives the check of a castling move, from between the king's squares.
+		let moved = match m.castling() {
+			true => (m.from() + m.to()) / 2,
+			false => m.to(),
+		};
+
+		stats.checks += 1;
+
+		if !BitboardUtils::occupied(checkers, moved) {
+			stats.discovered_checks += 1;
+		}
+
+		if checkers.count_ones() > 1 {
+			stats.double_checks += 1;
+		}
+
+		if !self.has_legal_move() {
+			stats.checkmates += 1;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn perft_stats_positions() {
+		let cases = [
+			(
+				"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+				vec![20, 400, 8902, 197281],
+				PerftStats {
+					nodes: 197281,
+					captures: 1576,
+					checks: 469,
+					checkmates: 8,
+					..PerftStats::default()
+				},
+			),
+			(
+				"r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+				vec![48, 2039, 97862],
+				PerftStats {
+					nodes: 97862,
+					captures: 17102,
+					en_passant: 45,
+					castles: 3162,
+					checks: 993,
+					checkmates: 1,
+					..PerftStats::default()
+				},
+			),
+			(
+				"8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+				vec![14, 191, 2812, 43238],
+				PerftStats {
+					nodes: 43238,
+					captures: 3348,
+					en_passant: 123,
+					checks: 1680,
+					discovered_checks: 106,
+					checkmates: 17,
+					..PerftStats::default()
+				},
+			),
+		];
+
+		for (fen, nodes, deepest) in cases {
+			let mut chess = Chess::try_from(fen).unwrap();
+			let (stats, _) = chess.perft_stats(nodes.len() as u8, 1);
+
+			assert_eq!(
+				stats.iter().map(|stats| stats.nodes).collect::<Vec<_>>(),
+				nodes,
+				"{fen}"
+			);
+			assert_eq!(stats.last(), Some(&deepest), "{fen}");
+
+			// Depths at or above the split ply are recorded without any task.
+			for depth in 1..=SPLIT_PLY {
+				let (shallow, _) = chess.perft_stats(depth, 1);
+
+				assert_eq!(shallow, stats[..depth as usize], "{fen} {depth}");
+			}
+		}
+	}
+
+	#[test]
+	fn hashed_perft_matches_unhashed() {
+		let cases = [
+			(
+				"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+				4,
+				197281,
+			),
+			(
+				"r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+				4,
+				4085603,
+			),
+			("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 5, 674624),
+			// The knight and the a1 rook both going out and back reach the same placement with
+			// different castling rights.
+			("4k3/8/8/8/8/8/8/R3K1NR w KQ - 0 1", 5, 602333),
+		];
+
+		for (fen, depth, nodes) in cases {
+			let mut chess = Chess::try_from(fen).unwrap();
+
+			assert_eq!(chess.perft(depth, 1, Some(1 << 20)).nodes, nodes, "{fen}");
+			assert_eq!(chess.perft(depth, 1, None).nodes, nodes, "{fen}");
+		}
+	}
 }
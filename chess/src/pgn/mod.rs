@@ -0,0 +1,304 @@
+mod reader;
+mod writer;
+
+pub use reader::PgnReader;
+
+use crate::{
+	board::{color::ColorUtils, FenError},
+	move_gen::Move,
+	notation::MoveParseError,
+	Chess,
+};
+use std::{error::Error, fmt, io, str::FromStr};
+
+const START_POSITION: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult {
+	WhiteWins,
+	BlackWins,
+	Draw,
+	/// The game is unfinished or its result is unknown, written as `*`.
+	#[default]
+	Unknown,
+}
+
+impl fmt::Display for GameResult {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::WhiteWins => "1-0",
+				Self::BlackWins => "0-1",
+				Self::Draw => "1/2-1/2",
+				Self::Unknown => "*",
+			}
+		)
+	}
+}
+
+impl GameResult {
+	pub fn try_parse(text: &str) -> Option<Self> {
+		match text {
+			"1-0" => Some(Self::WhiteWins),
+			"0-1" => Some(Self::BlackWins),
+			"1/2-1/2" => Some(Self::Draw),
+			"*" => Some(Self::Unknown),
+			_ => None,
+		}
+	}
+}
+
+/// A move of a game together with its annotations and the alternatives to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgnMove {
+	pub m: Move,
+	/// Numeric annotation glyphs, with `!` and `?` style suffixes read as `$1` to `$6`.
+	pub nags: Vec<u8>,
+	pub comments: Vec<String>,
+	/// Lines played instead of this move, starting from the position before it.
+	pub variations: Vec<Variation>,
+}
+
+impl PgnMove {
+	pub fn new(m: Move) -> Self {
+		Self {
+			m,
+			nags: Vec::new(),
+			comments: Vec::new(),
+			variations: Vec::new(),
+		}
+	}
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Variation {
+	/// A comment placed before the first move of the line.
+	pub comment: Option<String>,
+	pub moves: Vec<PgnMove>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Game {
+	/// Tag pairs in the order they appear.
+	pub tags: Vec<(String, String)>,
+	/// The position the game starts from, taken from the `FEN` tag when present.
+	pub start: Chess,
+	pub comment: Option<String>,
+	pub moves: Vec<PgnMove>,
+	pub result: GameResult,
+}
+
+impl Default for Game {
+	fn default() -> Self {
+		Self::new(Chess::default())
+	}
+}
+
+impl Game {
+	/// An empty game from `start`, tagged with the Seven Tag Roster.
+	pub fn new(start: Chess) -> Self {
+		let mut game = Self {
+			tags: Vec::new(),
+			start,
+			comment: None,
+			moves: Vec::new(),
+			result: GameResult::Unknown,
+		};
+
+		for (name, value) in [
+			("Event", "?"),
+			("Site", "?"),
+			("Date", "????.??.??"),
+			("Round", "?"),
+			("White", "?"),
+			("Black", "?"),
+			("Result", "*"),
+		] {
+			game.set_tag(name, value);
+		}
+
+		let fen = game.start.board.to_fen();
+
+		if fen != START_POSITION {
+			game.set_tag("SetUp", "1");
+			game.set_tag("FEN", &fen);
+		}
+
+		game
+	}
+
+	pub fn tag(&self, name: &str) -> Option<&str> {
+		self.tags
+			.iter()
+			.find(|(tag, _)| tag == name)
+			.map(|(_, value)| value.as_str())
+	}
+
+	/// Replaces the value of a tag, appending it if the game does not have it yet.
+	pub fn set_tag(&mut self, name: &str, value: &str) {
+		match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+			Some((_, old)) => *old = value.to_string(),
+			None => self.tags.push((name.to_string(), value.to_string())),
+		}
+	}
+
+	pub fn set_result(&mut self, result: GameResult) {
+		self.result = result;
+		self.set_tag("Result", &result.to_string());
+	}
+
+	/// Plays the main line from the start position, returning the final position with its
+	/// history.
+	pub fn replay(&self) -> Chess {
+		let mut chess = self.start.clone();
+
+		for pgn_move in &self.moves {
+			chess.make_move(pgn_move.m);
+		}
+
+		chess
+	}
+}
+
+impl From<&Chess> for Game {
+	/// Records the moves played in `chess` as a game, with the result taken from its outcome.
+	fn from(chess: &Chess) -> Self {
+		let mut start = chess.clone();
+		let mut moves = Vec::with_capacity(chess.history.len());
+
+		for state in chess.history.iter().rev() {
+			moves.push(PgnMove::new(state.move_made));
+			start.undo_move();
+		}
+
+		moves.reverse();
+
+		let mut game = Self::new(start);
+		game.moves = moves;
+		game.set_result(match chess.outcome() {
+			Some(outcome) => match outcome.winner() {
+				Some(ColorUtils::WHITE) => GameResult::WhiteWins,
+				Some(_) => GameResult::BlackWins,
+				None => GameResult::Draw,
+			},
+			None => GameResult::Unknown,
+		});
+
+		game
+	}
+}
+
+impl FromStr for Game {
+	type Err = PgnError;
+
+	/// Parses the first game of a PGN text.
+	fn from_str(text: &str) -> Result<Self, Self::Err> {
+		PgnReader::new(text.as_bytes())
+			.next()
+			.unwrap_or(Err(PgnError::NoGame))
+	}
+}
+
+#[derive(Debug)]
+pub enum PgnError {
+	Io(io::Error),
+	/// The input does not contain a game.
+	NoGame,
+	/// A tag pair is not of the form `[Name "value"]`.
+	InvalidTag(String),
+	/// The `FEN` tag does not describe a position.
+	InvalidFen(FenError),
+	/// A move of the movetext cannot be played.
+	InvalidMove {
+		fullmove_number: u16,
+		error: MoveParseError,
+	},
+	/// A token that is not allowed at its place in the movetext.
+	UnexpectedToken(String),
+	UnterminatedComment,
+	UnterminatedString,
+	UnbalancedVariation,
+}
+
+impl fmt::Display for PgnError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Io(error) => write!(f, "{error}"),
+			Self::NoGame => write!(f, "no game found"),
+			Self::InvalidTag(tag) => write!(f, "invalid tag pair: {tag}"),
+			Self::InvalidFen(error) => write!(f, "invalid FEN tag: {error}"),
+			Self::InvalidMove {
+				fullmove_number,
+				error,
+			} => write!(f, "move {fullmove_number}: {error}"),
+			Self::UnexpectedToken(token) => write!(f, "unexpected token: '{token}'"),
+			Self::UnterminatedComment => write!(f, "unterminated comment"),
+			Self::UnterminatedString => write!(f, "unterminated string"),
+			Self::UnbalancedVariation => write!(f, "unbalanced variation parentheses"),
+		}
+	}
+}
+
+impl Error for PgnError {}
+
+impl From<io::Error> for PgnError {
+	fn from(error: io::Error) -> Self {
+		Self::Io(error)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const GAME: &str = r#"[Event "Casual \"blitz\""]
+[Site "?"]
+[Date "2024.01.01"]
+[Round "1"]
+[White "White"]
+[Black "Black"]
+[Result "0-1"]
+
+{Opening} 1. e4 e5 2. Nf3 $1 {The main move} (2. f4 exf4 3. Nf3 (3. Bc4) 3...
+g5) 2... Nc6 3. Bc4 Nd4 4. Nxe5 $2 Qg5 5. Nxf7 Qxg2 6. Rf1 Qxe4+ 7. Be2 Nf3# 0-1
+"#;
+
+	#[test]
+	fn read_and_write() {
+		let game = GAME.parse::<Game>().unwrap();
+
+		assert_eq!(game.tag("Event"), Some("Casual \"blitz\""));
+		assert_eq!(game.comment.as_deref(), Some("Opening"));
+		assert_eq!(game.moves.len(), 14);
+		assert_eq!(game.moves[2].nags, [1]);
+		assert_eq!(game.moves[2].comments, ["The main move"]);
+		assert_eq!(game.moves[2].variations[0].moves.len(), 4);
+		assert_eq!(game.moves[2].variations[0].moves[2].variations.len(), 1);
+		assert_eq!(game.result, GameResult::BlackWins);
+		assert!(game.replay().outcome().is_some());
+		assert_eq!(game.to_string(), GAME);
+	}
+
+	#[test]
+	fn round_trip() {
+		let mut chess =
+			Chess::try_from("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+				.unwrap();
+
+		for text in ["e1g1", "e8c8", "d5e6", "b4c3", "e6f7", "c3b2", "f7f8q"] {
+			let m = chess.parse_uci_move(text).unwrap();
+			chess.make_move(m);
+		}
+
+		let game = Game::from(&chess);
+		let text = game.to_string();
+		let read = text.parse::<Game>().unwrap();
+
+		assert_eq!(read.tags, game.tags);
+		assert_eq!(read.moves, game.moves);
+		assert_eq!(read.replay().board.to_fen(), chess.board.to_fen());
+		assert_eq!(read.to_string(), text);
+	}
+}
@@ -0,0 +1,359 @@
+use super::{Game, GameResult, PgnError, PgnMove, Variation};
+use crate::Chess;
+use std::{
+	io::{BufRead, Lines},
+	iter::Peekable,
+	str::Chars,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+	TagOpen,
+	TagClose,
+	String(String),
+	Symbol(String),
+	Comment(String),
+	Nag(u8),
+	VariationOpen,
+	VariationClose,
+}
+
+/// Reads the games of a PGN file one at a time.
+pub struct PgnReader<R> {
+	lines: Lines<R>,
+	pending: Option<String>,
+	chess: Chess,
+}
+
+impl<R: BufRead> PgnReader<R> {
+	pub fn new(reader: R) -> Self {
+		Self::with_chess(reader, Chess::default())
+	}
+
+	/// Creates a reader whose games share the move generator of `chess`.
+	pub fn with_chess(reader: R, chess: Chess) -> Self {
+		Self {
+			lines: reader.lines(),
+			pending: None,
+			chess: chess.position(),
+		}
+	}
+
+	/// Collects the lines of the next game, which ends at its result or where the tag pairs of
+	/// another begin.
+	fn next_game_text(&mut self) -> Result<Option<String>, PgnError> {
+		let mut text = String::new();
+		let mut has_tags = false;
+		let mut in_movetext = false;
+		let mut in_comment = false;
+
+		loop {
+			let line = match self.pending.take() {
+				Some(line) => line,
+				None => match self.lines.next() {
+					Some(line) => line?,
+					None => break,
+				},
+			};
+
+			let trimmed = line.trim();
+
+			if !in_comment {
+				if trimmed.starts_with('%') {
+					continue;
+				}
+
+				if trimmed.starts_with('[') {
+					if in_movetext {
+						self.pending = Some(line);
+						break;
+					}
+
+					has_tags = true;
+					text += &line;
+					text.push('\n');
+					continue;
+				}
+
+				in_movetext |= !trimmed.is_empty() || has_tags;
+			}
+
+			let (open_comment, terminated) = scan_line(&line, in_comment);
+			in_comment = open_comment;
+
+			text += &line;
+			text.push('\n');
+
+			if terminated {
+				break;
+			}
+		}
+
+		Ok((!text.trim().is_empty()).then_some(text))
+	}
+}
+
+impl<R: BufRead> Iterator for PgnReader<R> {
+	type Item = Result<Game, PgnError>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		match self.next_game_text() {
+			Ok(Some(text)) => Some(tokenize(&text).and_then(|tokens| parse(tokens, &self.chess))),
+			Ok(None) => None,
+			Err(error) => Some(Err(error)),
+		}
+	}
+}
+
+/// Returns whether the line leaves a brace comment open and whether it contains a game result
+/// outside of comments.
+fn scan_line(line: &str, mut in_comment: bool) -> (bool, bool) {
+	let mut word = String::new();
+	let mut terminated = false;
+
+	for ch in line.chars().chain([' ']) {
+		match (in_comment, ch) {
+			(true, '}') => in_comment = false,
+			(true, _) => {}
+			(false, ';') => break,
+			(false, ch) if ch.is_whitespace() || matches!(ch, '{' | '(' | ')') => {
+				terminated |= GameResult::try_parse(&word).is_some();
+				in_comment = ch == '{';
+				word.clear();
+			}
+			(false, ch) => word.push(ch),
+		}
+	}
+
+	terminated |= GameResult::try_parse(&word).is_some();
+
+	(in_comment, terminated)
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, PgnError> {
+	let mut tokens = Vec::new();
+	let mut chars = text.chars().peekable();
+
+	while let Some(ch) = chars.next() {
+		let token = match ch {
+			'[' => Token::TagOpen,
+			']' => Token::TagClose,
+			'(' => Token::VariationOpen,
+			')' => Token::VariationClose,
+			'{' => {
+				let comment = take_while(&mut chars, |ch| ch != '}');
+
+				if chars.next().is_none() {
+					return Err(PgnError::UnterminatedComment);
+				}
+
+				Token::Comment(normalize(&comment))
+			}
+			';' => Token::Comment(normalize(&take_while(&mut chars, |ch| ch != '\n'))),
+			'"' => Token::String(read_string(&mut chars)?),
+			'$' => {
+				let digits = take_while(&mut chars, |ch| ch.is_ascii_digit());
+
+				Token::Nag(
+					digits
+						.parse()
+						.map_err(|_| PgnError::UnexpectedToken(format!("${digits}")))?,
+				)
+			}
+			'!' | '?' => {
+				let suffix = ch.to_string() + &take_while(&mut chars, |ch| matches!(ch, '!' | '?'));
+
+				Token::Nag(match suffix.as_str() {
+					"!" => 1,
+					"?" => 2,
+					"!!" => 3,
+					"??" => 4,
+					"!?" => 5,
+					"?!" => 6,
+					_ => return Err(PgnError::UnexpectedToken(suffix)),
+				})
+			}
+			'.' => continue,
+			ch if ch.is_whitespace() => continue,
+			ch if ch.is_ascii_alphanumeric() || ch == '*' => {
+				let symbol = ch.to_string()
+					+ &take_while(&mut chars, |ch| {
+						ch.is_ascii_alphanumeric()
+							|| matches!(ch, '_' | '+' | '#' | '=' | ':' | '-' | '/')
+					});
+
+				Token::Symbol(symbol)
+			}
+			ch => return Err(PgnError::UnexpectedToken(ch.to_string())),
+		};
+
+		tokens.push(token);
+	}
+
+	Ok(tokens)
+}
+
+fn take_while(chars: &mut Peekable<Chars>, predicate: impl Fn(char) -> bool) -> String {
+	let mut text = String::new();
+
+	while let Some(ch) = chars.next_if(|ch| predicate(*ch)) {
+		text.push(ch);
+	}
+
+	text
+}
+
+fn read_string(chars: &mut Peekable<Chars>) -> Result<String, PgnError> {
+	let mut text = String::new();
+
+	loop {
+		match chars.next() {
+			Some('"') => return Ok(text),
+			Some('\\') => match chars.next() {
+				Some(ch) => text.push(ch),
+				None => return Err(PgnError::UnterminatedString),
+			},
+			Some(ch) => text.push(ch),
+			None => return Err(PgnError::UnterminatedString),
+		}
+	}
+}
+
+fn normalize(comment: &str) -> String {
+	comment.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A line of play being read, with the position reached at its end.
+struct Line {
+	chess: Chess,
+	variation: Variation,
+}
+
+fn parse(tokens: Vec<Token>, chess: &Chess) -> Result<Game, PgnError> {
+	let mut tokens = tokens.into_iter().peekable();
+	let mut tags = Vec::new();
+
+	while tokens.next_if_eq(&Token::TagOpen).is_some() {
+		match (tokens.next(), tokens.next(), tokens.next()) {
+			(Some(Token::Symbol(name)), Some(Token::String(value)), Some(Token::TagClose)) => {
+				tags.push((name, value));
+			}
+			(name, value, _) => {
+				return Err(PgnError::InvalidTag(format!("{name:?} {value:?}")));
+			}
+		}
+	}
+
+	let mut start = chess.clone();
+
+	if let Some((_, fen)) = tags.iter().find(|(name, _)| name == "FEN") {
+		start.set_position(fen).map_err(PgnError::InvalidFen)?;
+	}
+
+	let mut lines = vec![Line {
+		chess: start.clone(),
+		variation: Variation::default(),
+	}];
+	let mut result = None;
+
+	for token in tokens {
+		let line = lines.last_mut().unwrap();
+
+		match token {
+			Token::Symbol(symbol) => {
+				if let Some(game_result) = GameResult::try_parse(&symbol) {
+					if lines.len() > 1 {
+						return Err(PgnError::UnbalancedVariation);
+					}
+
+					result = Some(game_result);
+					break;
+				}
+
+				if symbol.chars().all(|ch| ch.is_ascii_digit()) {
+					continue;
+				}
+
+				let m = line
+					.chess
+					.parse_san(&symbol)
+					.map_err(|error| PgnError::InvalidMove {
+						fullmove_number: line.chess.board.fullmove_number,
+						error,
+					})?;
+
+				line.chess.make_move(m);
+				line.variation.moves.push(PgnMove::new(m));
+			}
+			Token::Comment(comment) => match line.variation.moves.last_mut() {
+				Some(pgn_move) => pgn_move.comments.push(comment),
+				None => {
+					let leading = line.variation.comment.get_or_insert_with(String::new);
+
+					if !leading.is_empty() {
+						leading.push(' ');
+					}
+
+					leading.push_str(&comment);
+				}
+			},
+			Token::Nag(nag) => match line.variation.moves.last_mut() {
+				Some(pgn_move) => pgn_move.nags.push(nag),
+				None => return Err(PgnError::UnexpectedToken(format!("${nag}"))),
+			},
+			Token::VariationOpen => {
+				if line.variation.moves.is_empty() {
+					return Err(PgnError::UnbalancedVariation);
+				}
+
+				let mut chess = line.chess.clone();
+				chess.undo_move();
+
+				lines.push(Line {
+					chess,
+					variation: Variation::default(),
+				});
+			}
+			Token::VariationClose => {
+				if lines.len() == 1 {
+					return Err(PgnError::UnbalancedVariation);
+				}
+
+				let variation = lines.pop().unwrap().variation;
+				let parent = lines.last_mut().unwrap();
+
+				parent
+					.variation
+					.moves
+					.last_mut()
+					.unwrap()
+					.variations
+					.push(variation);
+			}
+			Token::TagOpen => return Err(PgnError::UnexpectedToken("[".to_string())),
+			Token::TagClose => return Err(PgnError::UnexpectedToken("]".to_string())),
+			Token::String(text) => return Err(PgnError::UnexpectedToken(format!("\"{text}\""))),
+		}
+	}
+
+	if lines.len() > 1 {
+		return Err(PgnError::UnbalancedVariation);
+	}
+
+	let main = lines.pop().unwrap().variation;
+	let result = result
+		.or_else(|| {
+			tags.iter()
+				.find(|(name, _)| name == "Result")
+				.and_then(|(_, value)| GameResult::try_parse(value))
+		})
+		.unwrap_or_default();
+
+	Ok(Game {
+		tags,
+		start,
+		comment: main.comment,
+		moves: main.moves,
+		result,
+	})
+}
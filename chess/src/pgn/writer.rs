@@ -0,0 +1,113 @@
+use super::{Game, PgnMove};
+use crate::{board::color::ColorUtils, Chess};
+use std::fmt;
+
+const MAX_LINE_LENGTH: usize = 80;
+
+impl fmt::Display for Game {
+	/// Writes the game in PGN export format, wrapping the movetext at 80 columns.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (name, value) in &self.tags {
+			let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+
+			writeln!(f, "[{name} \"{value}\"]")?;
+		}
+
+		if !self.tags.is_empty() {
+			writeln!(f)?;
+		}
+
+		let mut tokens = Vec::new();
+
+		if let Some(comment) = &self.comment {
+			push_comment(&mut tokens, comment);
+		}
+
+		push_moves(&mut tokens, self.start.clone(), &self.moves);
+		tokens.push(self.result.to_string());
+
+		let mut line = String::new();
+
+		for token in tokens {
+			if !line.is_empty() && line.len() + 1 + token.len() > MAX_LINE_LENGTH {
+				writeln!(f, "{line}")?;
+				line.clear();
+			}
+
+			if !line.is_empty() {
+				line.push(' ');
+			}
+
+			line += &token;
+		}
+
+		writeln!(f, "{line}")
+	}
+}
+
+/// Splits a comment into words so that long comments wrap like the rest of the movetext.
+fn push_comment(tokens: &mut Vec<String>, comment: &str) {
+	let words = comment.split_whitespace().collect::<Vec<_>>();
+
+	match words.len() {
+		0 => tokens.push("{}".to_string()),
+		length => {
+			for (index, word) in words.into_iter().enumerate() {
+				let mut token = word.to_string();
+
+				if index == 0 {
+					token.insert(0, '{');
+				}
+
+				if index == length - 1 {
+					token.push('}');
+				}
+
+				tokens.push(token);
+			}
+		}
+	}
+}
+
+fn push_moves(tokens: &mut Vec<String>, mut chess: Chess, moves: &[PgnMove]) {
+	let mut needs_number = true;
+
+	for pgn_move in moves {
+		let fullmove_number = chess.board.fullmove_number;
+
+		match chess.board.color {
+			ColorUtils::WHITE => tokens.push(format!("{fullmove_number}.")),
+			_ if needs_number => tokens.push(format!("{fullmove_number}...")),
+			_ => {}
+		}
+
+		tokens.push(chess.to_san(pgn_move.m));
+		tokens.extend(pgn_move.nags.iter().map(|nag| format!("${nag}")));
+
+		for comment in &pgn_move.comments {
+			push_comment(tokens, comment);
+		}
+
+		for variation in &pgn_move.variations {
+			let first = tokens.len();
+
+			if let Some(comment) = &variation.comment {
+				push_comment(tokens, comment);
+			}
+
+			push_moves(tokens, chess.clone(), &variation.moves);
+
+			match tokens.len() > first {
+				true => {
+					tokens[first].insert(0, '(');
+					tokens.last_mut().unwrap().push(')');
+				}
+				false => tokens.push("()".to_string()),
+			}
+		}
+
+		needs_number = !pgn_move.comments.is_empty() || !pgn_move.variations.is_empty();
+
+		chess.make_move(pgn_move.m);
+	}
+}
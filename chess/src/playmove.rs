@@ -9,8 +9,32 @@ use crate::{
 };
 
 impl Chess {
+	/// Plays a pseudo-legal move, taking it back and returning `false` if it leaves the king in
+	/// check.
 	#[inline(always)]
 	pub fn play_move(&mut self, m: Move) -> bool {
+		self.make_move(m);
+
+		let board = &self.board;
+		let color = board.color ^ 1;
+
+		let legal = !self.move_gen.square_attacked(
+			board,
+			board.color,
+			BitboardUtils::lsb(board.pieces[color][PieceUtils::KING]),
+		);
+
+		if !legal {
+			self.undo_move();
+		}
+
+		legal
+	}
+
+	/// Plays a move without checking whether it leaves the king in check, for moves that come
+	/// from `generate_legal_moves`.
+	#[inline(always)]
+	pub fn make_move(&mut self, m: Move) {
 		let board = &mut self.board;
 
 		self.history.push(OldState::new(board, m));
@@ -56,10 +80,6 @@ impl Chess {
 			if m.en_passant() {
 				board.remove_piece(PieceUtils::PAWN, opponent, to ^ 8);
 			}
-
-			if m.two_step() {
-				board.set_en_passant(to ^ 8);
-			}
 		} else {
 			board.remove_piece(piece, color, from);
 			board.add_piece(piece, color, to);
@@ -93,24 +113,16 @@ impl Chess {
 
 		board.switch_color();
 
+		if m.two_step() {
+			board.set_en_passant(to ^ 8);
+		}
+
 		if color == ColorUtils::BLACK {
 			board.fullmove_number += 1;
 		}
 
-		let legal = !self.move_gen.square_attacked(
-			board,
-			opponent,
-			BitboardUtils::lsb(board.pieces[color][PieceUtils::KING]),
-		);
-
 		#[cfg(debug_assertions)]
 		debug_assert!(debug::check_incrementals(board));
-
-		if !legal {
-			self.undo_move();
-		}
-
-		legal
 	}
 }
 
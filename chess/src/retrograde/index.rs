@@ -0,0 +1,206 @@
+use super::{Material, MAX_PIECES};
+use crate::board::{
+	bitboard::BitboardUtils,
+	color::ColorUtils,
+	file_rank::{FileUtils, RankUtils},
+	square::SquareUtils,
+	Board, Color, Piece, Square,
+};
+use std::ops::Range;
+
+/// The squares of a position's pieces, in material order.
+pub(crate) type Squares = [Square; MAX_PIECES];
+
+/// The squares white's king is kept on without pawns: the a1-d1-d4 triangle, which every square
+/// is mapped to by a rotation or reflection of the board.
+const TRIANGLE: [Square; 10] = [
+	SquareUtils::A1,
+	SquareUtils::B1,
+	SquareUtils::C1,
+	SquareUtils::D1,
+	SquareUtils::B2,
+	SquareUtils::C2,
+	SquareUtils::D2,
+	SquareUtils::C3,
+	SquareUtils::D3,
+	SquareUtils::D4,
+];
+
+const FLIP_FILE: usize = 1;
+const FLIP_RANK: usize = 1 << 1;
+const TRANSPOSE: usize = 1 << 2;
+
+/// Maps positions of a material to table indices. Positions equal up to a symmetry of the board
+/// share an index: pawnless positions are reflected so that white's king is in the a1-d1-d4
+/// triangle, positions with pawns only from the kingside to the queenside. The side to move and
+/// white's king make up the top of the index, every other piece adds its square as a base 64
+/// digit.
+#[derive(Debug, Clone)]
+pub(crate) struct Indexer {
+	pieces: Vec<(Color, Piece)>,
+	/// The runs of identical pieces, whose squares are sorted so that swapping them does not
+	/// change the index.
+	groups: Vec<Range<usize>>,
+	pawns: bool,
+	kings: Vec<Square>,
+	king_slots: [usize; SquareUtils::SIZE],
+	size: usize,
+}
+
+impl Indexer {
+	pub(crate) fn new(material: &Material) -> Self {
+		let pieces = material.pieces().to_vec();
+		let pawns = material.has_pawns();
+
+		let kings = match pawns {
+			true => (0..SquareUtils::SIZE)
+				.filter(|&square| SquareUtils::location(square).0 <= FileUtils::D)
+				.collect::<Vec<_>>(),
+			false => TRIANGLE.to_vec(),
+		};
+
+		let mut king_slots = [0; SquareUtils::SIZE];
+
+		for (slot, &square) in kings.iter().enumerate() {
+			king_slots[square] = slot;
+		}
+
+		let mut groups = Vec::new();
+		let mut start = 0;
+
+		for end in 1..=pieces.len() {
+			if end == pieces.len() || pieces[end] != pieces[start] {
+				groups.push(start..end);
+				start = end;
+			}
+		}
+
+		let size = ColorUtils::SIZE * kings.len() * SquareUtils::SIZE.pow(pieces.len() as u32 - 1);
+
+		Self {
+			pieces,
+			groups,
+			pawns,
+			kings,
+			king_slots,
+			size,
+		}
+	}
+
+	/// The number of indices, legal positions or not.
+	#[inline(always)]
+	pub(crate) fn size(&self) -> usize {
+		self.size
+	}
+
+	/// The side to move and squares of an index.
+	pub(crate) fn decode(&self, index: usize) -> (Color, Squares) {
+		let mut squares = [0; MAX_PIECES];
+		let mut rest = index;
+
+		for square in squares[1..self.pieces.len()].iter_mut().rev() {
+			*square = rest % SquareUtils::SIZE;
+			rest /= SquareUtils::SIZE;
+		}
+
+		squares[0] = self.kings[rest % self.kings.len()];
+
+		(rest / self.kings.len(), squares)
+	}
+
+	/// The index of a position, the same for all of its symmetric images.
+	pub(crate) fn index(&self, color: Color, squares: &Squares) -> usize {
+		let squares = self.canonical(squares);
+		let top = color * self.kings.len() + self.king_slots[squares[0]];
+
+		squares[1..self.pieces.len()]
+			.iter()
+			.fold(top, |index, &square| index * SquareUtils::SIZE + square)
+	}
+
+	/// The index of a board, with the colours swapped when its material is that of the table
+	/// flipped. `None` when the board has other pieces.
+	pub(crate) fn board_index(&self, board: &Board) -> Option<usize> {
+		if board.occupancy.count_ones() as usize != self.pieces.len() {
+			return None;
+		}
+
+		[0, 1].into_iter().find_map(|flip| {
+			let mut squares = [0; MAX_PIECES];
+
+			for group in &self.groups {
+				let (color, piece) = self.pieces[group.start];
+				let mut bitboard = board.pieces[color ^ flip][piece];
+
+				if bitboard.count_ones() as usize != group.len() {
+					return None;
+				}
+
+				for square in &mut squares[group.clone()] {
+					*square = BitboardUtils::pop_lsb(&mut bitboard) ^ (flip * 56);
+				}
+			}
+
+			Some(self.index(board.color ^ flip, &squares))
+		})
+	}
+
+	/// The symmetric image of a position stored in the table. When white's king is on the
+	/// diagonal of the triangle both images keeping it there are considered, and the smallest
+	/// wins.
+	pub(crate) fn canonical(&self, squares: &Squares) -> Squares {
+		let (file, rank) = SquareUtils::location(squares[0]);
+		let mut symmetry = match file > FileUtils::D {
+			true => FLIP_FILE,
+			false => 0,
+		};
+
+		if self.pawns {
+			return self.transform(symmetry, squares);
+		}
+
+		if rank > RankUtils::R4 {
+			symmetry |= FLIP_RANK;
+		}
+
+		let (file, rank) = SquareUtils::location(transform(symmetry, squares[0]));
+
+		match rank.cmp(&file) {
+			std::cmp::Ordering::Less => self.transform(symmetry, squares),
+			std::cmp::Ordering::Greater => self.transform(symmetry | TRANSPOSE, squares),
+			std::cmp::Ordering::Equal => self
+				.transform(symmetry, squares)
+				.min(self.transform(symmetry | TRANSPOSE, squares)),
+		}
+	}
+
+	fn transform(&self, symmetry: usize, squares: &Squares) -> Squares {
+		let mut squares = squares.map(|square| transform(symmetry, square));
+
+		for group in &self.groups {
+			squares[group.clone()].sort_unstable();
+		}
+
+		squares[self.pieces.len()..].fill(0);
+		squares
+	}
+}
+
+#[inline(always)]
+fn transform(symmetry: usize, square: Square) -> Square {
+	let mut square = square;
+
+	if symmetry & FLIP_FILE != 0 {
+		square ^= 7;
+	}
+
+	if symmetry & FLIP_RANK != 0 {
+		square ^= 56;
+	}
+
+	if symmetry & TRANSPOSE != 0 {
+		square = square >> 3 | (square & 7) << 3;
+	}
+
+	square
+}
@@ -0,0 +1,209 @@
+use super::MAX_PIECES;
+use crate::board::{color::ColorUtils, piece::PieceUtils, Board, Color, Piece};
+use std::{error::Error, fmt, str::FromStr};
+
+/// The pieces of an ending, kings included, such as `KQvKR`. White's pieces come first, each
+/// side ordered from its king down to its pawns.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Material {
+	pieces: Vec<(Color, Piece)>,
+}
+
+impl Material {
+	/// Sorts the pieces into table order, checking that each side has a single king.
+	pub fn new(mut pieces: Vec<(Color, Piece)>) -> Result<Self, MaterialError> {
+		if pieces.len() > MAX_PIECES {
+			return Err(MaterialError::TooManyPieces(pieces.len()));
+		}
+
+		for color in [ColorUtils::WHITE, ColorUtils::BLACK] {
+			let kings = pieces
+				.iter()
+				.filter(|&&piece| piece == (color, PieceUtils::KING))
+				.count();
+
+			if kings != 1 {
+				return Err(MaterialError::KingCount { color, kings });
+			}
+		}
+
+		pieces.sort_by_key(|&(color, piece)| (color, PieceUtils::KING - piece));
+
+		Ok(Self { pieces })
+	}
+
+	/// The material of a board, when it has few enough pieces for a table.
+	pub fn of(board: &Board) -> Result<Self, MaterialError> {
+		let mut pieces = Vec::new();
+
+		for color in [ColorUtils::WHITE, ColorUtils::BLACK] {
+			for piece in PieceUtils::RANGE {
+				let count = board.pieces[color][piece].count_ones() as usize;
+				pieces.extend(std::iter::repeat_n((color, piece), count));
+			}
+		}
+
+		Self::new(pieces)
+	}
+
+	pub fn pieces(&self) -> &[(Color, Piece)] {
+		&self.pieces
+	}
+
+	pub fn len(&self) -> usize {
+		self.pieces.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.pieces.is_empty()
+	}
+
+	pub fn has_pawns(&self) -> bool {
+		self.pieces
+			.iter()
+			.any(|&(_, piece)| piece == PieceUtils::PAWN)
+	}
+
+	/// The same pieces with the colours swapped.
+	pub fn flipped(&self) -> Self {
+		let pieces = self.pieces.iter().map(|&(color, piece)| (color ^ 1, piece));
+
+		Self::new(pieces.collect()).unwrap()
+	}
+
+	/// The material with the stronger side as white, which is how tables are stored, and whether
+	/// the colours had to be swapped.
+	pub fn normalized(&self) -> (Self, bool) {
+		let side = |color: Color| {
+			let pieces = self.pieces.iter().filter(|&&(c, _)| c == color);
+
+			(
+				pieces.clone().count(),
+				pieces.map(|&(_, piece)| piece).collect::<Vec<_>>(),
+			)
+		};
+
+		match side(ColorUtils::WHITE) >= side(ColorUtils::BLACK) {
+			true => (self.clone(), false),
+			false => (self.flipped(), true),
+		}
+	}
+
+	/// The normalized materials a capture or a promotion leads to.
+	pub fn conversions(&self) -> Vec<Self> {
+		let without = |pieces: &[(Color, Piece)], index: usize| {
+			let mut pieces = pieces.to_vec();
+			pieces.remove(index);
+			pieces
+		};
+
+		let mut conversions = Vec::new();
+
+		for (index, &(_, piece)) in self.pieces.iter().enumerate() {
+			if piece != PieceUtils::KING {
+				conversions.push(without(&self.pieces, index));
+			}
+		}
+
+		for (index, &(color, piece)) in self.pieces.iter().enumerate() {
+			if piece != PieceUtils::PAWN {
+				continue;
+			}
+
+			for promoted in PieceUtils::PROMOTIONS {
+				let mut pieces = self.pieces.clone();
+				pieces[index] = (color, promoted);
+
+				for (captured, &(other, piece)) in pieces.iter().enumerate() {
+					if other != color && piece != PieceUtils::KING {
+						conversions.push(without(&pieces, captured));
+					}
+				}
+
+				conversions.push(pieces);
+			}
+		}
+
+		let mut conversions = conversions
+			.into_iter()
+			.map(|pieces| Self::new(pieces).unwrap().normalized().0)
+			.collect::<Vec<_>>();
+
+		conversions.sort_by_key(|material| material.to_string());
+		conversions.dedup();
+		conversions
+	}
+}
+
+impl FromStr for Material {
+	type Err = MaterialError;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		let (white, black) = value
+			.split_once(['v', 'V'])
+			.ok_or(MaterialError::MissingSide)?;
+
+		let mut pieces = Vec::new();
+
+		for (color, side) in [(ColorUtils::WHITE, white), (ColorUtils::BLACK, black)] {
+			for character in side.chars() {
+				let piece = PieceUtils::try_parse(character.to_ascii_uppercase())
+					.ok_or(MaterialError::InvalidPiece(character))?;
+
+				pieces.push((color, piece));
+			}
+		}
+
+		Self::new(pieces)
+	}
+}
+
+impl fmt::Display for Material {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for &(color, piece) in &self.pieces {
+			if (color, piece) == (ColorUtils::BLACK, PieceUtils::KING) {
+				write!(f, "v")?;
+			}
+
+			write!(f, "{}", PieceUtils::to_string(piece, ColorUtils::WHITE))?;
+		}
+
+		Ok(())
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MaterialError {
+	/// The material does not separate white's pieces from black's with a `v`.
+	MissingSide,
+	InvalidPiece(char),
+	/// A side does not have exactly one king.
+	KingCount {
+		color: Color,
+		kings: usize,
+	},
+	/// The material has more pieces than tables can be solved for.
+	TooManyPieces(usize),
+}
+
+impl fmt::Display for MaterialError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::MissingSide => write!(f, "expected the pieces of both sides, e.g. KQvKR"),
+			Self::InvalidPiece(character) => write!(f, "invalid piece: {character}"),
+			Self::KingCount { color, kings } => {
+				let side = match *color {
+					ColorUtils::WHITE => "white",
+					_ => "black",
+				};
+
+				write!(f, "{side} has {kings} kings instead of one")
+			}
+			Self::TooManyPieces(pieces) => {
+				write!(f, "{pieces} pieces, but tables are limited to {MAX_PIECES}")
+			}
+		}
+	}
+}
+
+impl Error for MaterialError {}
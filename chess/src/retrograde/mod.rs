@@ -0,0 +1,61 @@
+mod index;
+mod material;
+mod solver;
+mod table;
+mod unmove;
+
+pub use material::{Material, MaterialError};
+pub use solver::Solver;
+pub use table::{Table, TableFile};
+
+/// The most pieces, kings included, a table can be solved for. Tables index every placement of
+/// the pieces, so each further piece multiplies their size by 64.
+pub const MAX_PIECES: usize = 4;
+
+/// The distance to mate of a position for the side to move, in plies. Wins take an odd number
+/// of plies and losses an even one, a loss in 0 being checkmate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dtm {
+	Win(u16),
+	Draw,
+	Loss(u16),
+}
+
+impl Dtm {
+	/// The number of moves of the winning side until mate, or `None` for a draw.
+	pub fn moves(self) -> Option<u16> {
+		match self {
+			Self::Win(plies) => Some(plies.div_ceil(2)),
+			Self::Loss(plies) => Some(plies / 2),
+			Self::Draw => None,
+		}
+	}
+
+	/// The outcome for the side that moved into the position.
+	pub(crate) fn before(self) -> Self {
+		match self {
+			Self::Win(plies) => Self::Loss(plies + 1),
+			Self::Draw => Self::Draw,
+			Self::Loss(plies) => Self::Win(plies + 1),
+		}
+	}
+
+	/// Orders outcomes from the side to move's point of view: quicker wins first and slower
+	/// losses last.
+	pub(crate) fn preference(self) -> i32 {
+		match self {
+			Self::Win(plies) => i32::MAX - plies as i32,
+			Self::Draw => 0,
+			Self::Loss(plies) => i32::MIN + plies as i32,
+		}
+	}
+
+	/// Decodes a table entry: zero for a draw, otherwise the plies to mate plus one.
+	pub(crate) fn from_entry(entry: u16) -> Self {
+		match entry {
+			0 => Self::Draw,
+			_ if entry.is_multiple_of(2) => Self::Win(entry - 1),
+			_ => Self::Loss(entry - 1),
+		}
+	}
+}
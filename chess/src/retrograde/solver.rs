@@ -0,0 +1,353 @@
+use super::{index::Indexer, unmove, Dtm, Material, Table};
+use crate::{
+	board::{
+		bitboard::BitboardUtils, file_rank::RankUtils, piece::PieceUtils, square::SquareUtils,
+		Board, Piece,
+	},
+	move_gen::{Move, MoveGen},
+	move_list::MoveList,
+};
+use std::{collections::HashMap, fmt, mem};
+
+/// Solves tables by retrograde analysis. Every table solved is kept, since the captures and
+/// promotions of a material are looked up in the tables they lead to.
+#[derive(Default)]
+pub struct Solver {
+	move_gen: MoveGen,
+	tables: HashMap<Material, Table>,
+}
+
+impl fmt::Debug for Solver {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Solver")
+			.field("tables", &self.tables.values())
+			.finish()
+	}
+}
+
+impl Solver {
+	/// Solves a material, after the materials its captures and promotions lead to. The table is
+	/// stored with the stronger side as white, see [`Material::normalized`].
+	pub fn solve(&mut self, material: &Material) -> &Table {
+		let (material, _) = material.normalized();
+
+		if !self.tables.contains_key(&material) {
+			for conversion in material.conversions() {
+				self.solve(&conversion);
+			}
+
+			let table = Solving::new(self, &material).run();
+			self.tables.insert(material.clone(), table);
+		}
+
+		&self.tables[&material]
+	}
+
+	/// The solved table of a material, in either colour.
+	pub fn table(&self, material: &Material) -> Option<&Table> {
+		self.tables.get(&material.normalized().0)
+	}
+
+	/// Takes the solved table of a material, in either colour, out of the solver.
+	pub fn remove(&mut self, material: &Material) -> Option<Table> {
+		self.tables.remove(&material.normalized().0)
+	}
+
+	/// The distance to mate of a board, when its material has been solved.
+	pub fn probe(&self, board: &Board) -> Option<Dtm> {
+		let material = Material::of(board).ok()?;
+
+		self.table(&material)?.probe(board)
+	}
+}
+
+/// A table being solved. Positions are settled ply by ply: a position is won in `n + 1` plies
+/// when a move leads to a loss in `n`, and lost in `n + 1` when the last of its moves turns out
+/// to lead to a win in `n`. Moves leaving the table settle a position at the ply they give.
+struct Solving<'a> {
+	solver: &'a Solver,
+	material: Material,
+	indexer: Indexer,
+	empty: Board,
+	/// Plies to mate plus one, or zero while unsettled.
+	values: Vec<u16>,
+	/// The in-table successors of each position not yet known to be won by the opponent.
+	counts: Vec<u8>,
+	/// The best outcome of each position among its captures and promotions.
+	exits: Vec<Option<Dtm>>,
+	/// The positions settled at each ply.
+	settled: Vec<Vec<usize>>,
+	/// The positions won by a capture or promotion, by the ply of that win.
+	pending: Vec<Vec<usize>>,
+}
+
+impl<'a> Solving<'a> {
+	fn new(solver: &'a Solver, material: &Material) -> Self {
+		let indexer = Indexer::new(material);
+		let size = indexer.size();
+
+		Self {
+			solver,
+			material: material.clone(),
+			indexer,
+			empty: Board::from("8/8/8/8/8/8/8/8 w - - 0 1"),
+			values: vec![0; size],
+			counts: vec![0; size],
+			exits: vec![None; size],
+			settled: Vec::new(),
+			pending: Vec::new(),
+		}
+	}
+
+	fn run(mut self) -> Table {
+		self.initialize();
+		self.induce();
+
+		Table::new(self.material, self.indexer, self.values)
+	}
+
+	/// Counts the in-table successors of each position, probes its captures and promotions, and
+	/// settles mates.
+	fn initialize(&mut self) {
+		let mut successors = Vec::new();
+
+		for index in 0..self.indexer.size() {
+			let Some(mut board) = self.position(index) else {
+				continue;
+			};
+
+			let mut list = MoveList::default();
+			self.solver.move_gen.legal(&board, &mut list);
+			successors.clear();
+
+			let mut exit = None::<Dtm>;
+
+			for &m in list.iter() {
+				play(&mut board, m);
+
+				match m.captured() != PieceUtils::NONE || m.promoted() != PieceUtils::NONE {
+					true => {
+						let outcome = self.solver.probe(&board).unwrap().before();
+
+						if exit.is_none_or(|exit| outcome.preference() > exit.preference()) {
+							exit = Some(outcome);
+						}
+					}
+					false => successors.push(self.indexer.board_index(&board).unwrap()),
+				}
+
+				undo(&mut board, m);
+			}
+
+			successors.sort_unstable();
+			successors.dedup();
+
+			self.counts[index] = successors.len() as u8;
+			self.exits[index] = exit;
+
+			if list.len() == 0 {
+				let king = BitboardUtils::lsb(board.pieces[board.color][PieceUtils::KING]);
+
+				if self
+					.solver
+					.move_gen
+					.square_attacked(&board, board.color ^ 1, king)
+				{
+					self.settle(index, 0);
+				}
+
+				continue;
+			}
+
+			match exit {
+				Some(Dtm::Win(plies)) => {
+					let plies = plies as usize;
+
+					if self.pending.len() <= plies {
+						self.pending.resize(plies + 1, Vec::new());
+					}
+
+					self.pending[plies].push(index);
+				}
+				Some(Dtm::Loss(plies)) if successors.is_empty() => self.settle(index, plies),
+				_ => {}
+			}
+		}
+	}
+
+	/// Walks back from the positions settled at each ply to the positions that lead to them.
+	fn induce(&mut self) {
+		let mut predecessors = Vec::new();
+		let mut plies = 0;
+
+		while plies < self.settled.len() || plies < self.pending.len() {
+			let pending = self.pending.get_mut(plies).map(mem::take);
+
+			for index in pending.unwrap_or_default() {
+				if self.values[index] == 0 {
+					self.settle(index, plies as u16);
+				}
+			}
+
+			let settled = self.settled.get_mut(plies).map(mem::take);
+
+			for index in settled.unwrap_or_default() {
+				let mut board = self.position(index).unwrap();
+
+				predecessors.clear();
+				unmove::unmoves(&self.solver.move_gen, &mut board, |board| {
+					predecessors.push(self.indexer.board_index(board).unwrap())
+				});
+				predecessors.sort_unstable();
+				predecessors.dedup();
+
+				for &predecessor in &predecessors {
+					if self.values[predecessor] != 0 {
+						continue;
+					}
+
+					if plies.is_multiple_of(2) {
+						self.settle(predecessor, plies as u16 + 1);
+						continue;
+					}
+
+					self.counts[predecessor] -= 1;
+
+					if self.counts[predecessor] > 0 {
+						continue;
+					}
+
+					match self.exits[predecessor] {
+						Some(Dtm::Win(_) | Dtm::Draw) => {}
+						Some(Dtm::Loss(exit)) => {
+							self.settle(predecessor, exit.max(plies as u16 + 1));
+						}
+						None => self.settle(predecessor, plies as u16 + 1),
+					}
+				}
+			}
+
+			plies += 1;
+		}
+	}
+
+	fn settle(&mut self, index: usize, plies: u16) {
+		let plies = plies as usize;
+
+		if self.settled.len() <= plies {
+			self.settled.resize(plies + 1, Vec::new());
+		}
+
+		self.values[index] = plies as u16 + 1;
+		self.settled[plies].push(index);
+	}
+
+	/// The position of an index, unless it is illegal or stored under another index.
+	fn position(&self, index: usize) -> Option<Board> {
+		let (color, squares) = self.indexer.decode(index);
+		let pieces = self.material.pieces();
+		let mut board = self.empty.clone();
+
+		for (&(color, piece), &square) in pieces.iter().zip(&squares) {
+			let (_, rank) = SquareUtils::location(square);
+
+			if BitboardUtils::occupied(board.occupancy, square)
+				|| piece == PieceUtils::PAWN && (rank == RankUtils::R1 || rank == RankUtils::R8)
+			{
+				return None;
+			}
+
+			board.add_piece(piece, color, square);
+		}
+
+		if self.indexer.canonical(&squares) != squares {
+			return None;
+		}
+
+		board.color = color;
+
+		let king = BitboardUtils::lsb(board.pieces[color ^ 1][PieceUtils::KING]);
+
+		match self.solver.move_gen.square_attacked(&board, color, king) {
+			true => None,
+			false => Some(board),
+		}
+	}
+}
+
+/// Makes a move on the board. Tables have no castling or en passant, so only the moved and
+/// captured pieces change.
+fn play(board: &mut Board, m: Move) {
+	let color = board.color;
+
+	if m.captured() != PieceUtils::NONE {
+		board.remove_piece(m.captured(), color ^ 1, m.to());
+	}
+
+	board.remove_piece(m.piece(), color, m.from());
+	board.add_piece(placed(m), color, m.to());
+	board.switch_color();
+}
+
+fn undo(board: &mut Board, m: Move) {
+	board.switch_color();
+
+	let color = board.color;
+
+	board.remove_piece(placed(m), color, m.to());
+	board.add_piece(m.piece(), color, m.from());
+
+	if m.captured() != PieceUtils::NONE {
+		board.add_piece(m.captured(), color ^ 1, m.to());
+	}
+}
+
+/// The piece standing on the target square after a move.
+#[inline(always)]
+fn placed(m: Move) -> Piece {
+	match m.promoted() {
+		PieceUtils::NONE => m.piece(),
+		promoted => promoted,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn material(text: &str) -> Material {
+		text.parse().unwrap()
+	}
+
+	fn board(fen: &str) -> Board {
+		fen.parse().unwrap()
+	}
+
+	#[test]
+	fn maximum_mate_lengths() {
+		let mut solver = Solver::default();
+
+		for (text, plies) in [("KQvK", 20), ("KRvK", 32), ("KPvK", 56)] {
+			assert_eq!(solver.solve(&material(text)).max_plies(), plies, "{text}");
+		}
+
+		// Tables are stored with the stronger side as white.
+		assert!(solver.table(&material("KvKQ")).is_some());
+		assert!(solver.table(&material("KBNvK")).is_none());
+	}
+
+	#[test]
+	fn probe() {
+		let mut solver = Solver::default();
+		solver.solve(&material("KQvK"));
+
+		let probe = |fen: &str| solver.probe(&board(fen));
+
+		assert_eq!(probe("k7/1Q6/1K6/8/8/8/8/8 b - - 0 1"), Some(Dtm::Loss(0)));
+		assert_eq!(probe("k7/8/1K6/8/8/8/7Q/8 w - - 0 1"), Some(Dtm::Win(1)));
+		assert_eq!(probe("K7/8/1k6/8/8/8/7q/8 b - - 0 1"), Some(Dtm::Win(1)));
+		assert_eq!(probe("k7/2Q5/1K6/8/8/8/8/8 b - - 0 1"), Some(Dtm::Draw));
+		assert_eq!(probe("k7/1Q6/8/8/8/8/8/7K b - - 0 1"), Some(Dtm::Draw));
+		assert_eq!(probe("k7/8/1K6/8/8/8/8/7R w - - 0 1"), None);
+	}
+}
@@ -0,0 +1,227 @@
+use super::{index::Indexer, Dtm, Material, MAX_PIECES};
+use crate::board::{color::ColorUtils, piece::PieceUtils, Board};
+use memmap2::Mmap;
+use std::{
+	fmt,
+	fs::File,
+	io::{self, Write},
+	path::Path,
+};
+
+const MAGIC: [u8; 4] = *b"RTBL";
+const VERSION: u8 = 1;
+const HEADER_SIZE: usize = 16;
+
+/// A solved table, with an entry per index: zero for draws and positions that are illegal or
+/// stored under another index, otherwise the plies to mate plus one.
+pub struct Table {
+	material: Material,
+	indexer: Indexer,
+	entries: Vec<u16>,
+}
+
+impl fmt::Debug for Table {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Table")
+			.field("material", &self.material.to_string())
+			.field("max_plies", &self.max_plies())
+			.finish()
+	}
+}
+
+impl Table {
+	pub(crate) fn new(material: Material, indexer: Indexer, entries: Vec<u16>) -> Self {
+		Self {
+			material,
+			indexer,
+			entries,
+		}
+	}
+
+	pub fn material(&self) -> &Material {
+		&self.material
+	}
+
+	/// The number of entries, legal positions or not.
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// The longest distance to mate of the table, in plies.
+	pub fn max_plies(&self) -> u16 {
+		self.entries
+			.iter()
+			.max()
+			.map_or(0, |entry| entry.saturating_sub(1))
+	}
+
+	/// The distance to mate of a board with the table's material, in either colour. Castling
+	/// rights and en passant squares are ignored.
+	pub fn probe(&self, board: &Board) -> Option<Dtm> {
+		let index = self.indexer.board_index(board)?;
+
+		Some(Dtm::from_entry(self.entries[index]))
+	}
+
+	/// Writes the table for [`TableFile`]: a 16 byte header with the material and the entry
+	/// width, then the entries in little-endian, a byte each when every distance fits.
+	pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+		let width = match self.entries.iter().all(|&entry| entry <= u8::MAX as u16) {
+			true => 1,
+			false => 2,
+		};
+
+		let mut header = [0; HEADER_SIZE];
+
+		header[..4].copy_from_slice(&MAGIC);
+		header[4] = VERSION;
+		header[5] = width as u8;
+		header[6] = self.material.len() as u8;
+
+		for (byte, &(color, piece)) in header[8..].iter_mut().zip(self.material.pieces()) {
+			*byte = (color << 3 | piece) as u8;
+		}
+
+		writer.write_all(&header)?;
+
+		for entry in &self.entries {
+			writer.write_all(&entry.to_le_bytes()[..width])?;
+		}
+
+		Ok(())
+	}
+}
+
+/// A table written by [`Table::write`], memory-mapped from its file.
+pub struct TableFile {
+	material: Material,
+	indexer: Indexer,
+	width: usize,
+	map: Mmap,
+}
+
+impl fmt::Debug for TableFile {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("TableFile")
+			.field("material", &self.material.to_string())
+			.field("width", &self.width)
+			.finish()
+	}
+}
+
+impl TableFile {
+	pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+		let file = File::open(path)?;
+
+		// Safety: the table is only read, and is expected not to change while it is open.
+		let map = unsafe { Mmap::map(&file)? };
+
+		let invalid = |error: &str| io::Error::new(io::ErrorKind::InvalidData, error.to_string());
+
+		let header = map
+			.get(..HEADER_SIZE)
+			.ok_or_else(|| invalid("table is shorter than its header"))?;
+
+		if header[..4] != MAGIC || header[4] != VERSION {
+			return Err(invalid("not a table of this version"));
+		}
+
+		let width = header[5] as usize;
+		let count = header[6] as usize;
+
+		if !(1..=2).contains(&width) || count > MAX_PIECES {
+			return Err(invalid("invalid table header"));
+		}
+
+		let pieces = header[8..8 + count]
+			.iter()
+			.map(|&byte| (byte as usize >> 3, byte as usize & 0x7))
+			.collect::<Vec<_>>();
+
+		if pieces
+			.iter()
+			.any(|&(color, piece)| color >= ColorUtils::SIZE || piece >= PieceUtils::SIZE)
+		{
+			return Err(invalid("invalid table header"));
+		}
+
+		let material = Material::new(pieces).map_err(|error| invalid(&error.to_string()))?;
+		let indexer = Indexer::new(&material);
+
+		if map.len() != HEADER_SIZE + indexer.size() * width {
+			return Err(invalid("table size does not match its material"));
+		}
+
+		Ok(Self {
+			material,
+			indexer,
+			width,
+			map,
+		})
+	}
+
+	pub fn material(&self) -> &Material {
+		&self.material
+	}
+
+	/// The distance to mate of a board with the table's material, in either colour. Castling
+	/// rights and en passant squares are ignored.
+	pub fn probe(&self, board: &Board) -> Option<Dtm> {
+		let index = self.indexer.board_index(board)?;
+		let offset = HEADER_SIZE + index * self.width;
+		let mut bytes = [0; 2];
+
+		bytes[..self.width].copy_from_slice(&self.map[offset..offset + self.width]);
+
+		Some(Dtm::from_entry(u16::from_le_bytes(bytes)))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::retrograde::Solver;
+	use std::{fs, io::BufWriter};
+
+	#[test]
+	fn write_and_open() {
+		let mut solver = Solver::default();
+		let table = solver.solve(&"KRvK".parse().unwrap());
+		let path = std::env::temp_dir().join(format!("krvk-{}.rtbl", std::process::id()));
+
+		let mut writer = BufWriter::new(File::create(&path).unwrap());
+		table.write(&mut writer).unwrap();
+		writer.flush().unwrap();
+		drop(writer);
+
+		let file = TableFile::open(&path).unwrap();
+
+		assert_eq!(file.material(), table.material());
+		assert_eq!(
+			fs::metadata(&path).unwrap().len() as usize,
+			HEADER_SIZE + table.len()
+		);
+
+		let mate = "k7/8/1K6/8/8/8/8/7R w - - 0 1".parse().unwrap();
+		assert_eq!(file.probe(&mate), Some(Dtm::Win(1)));
+
+		for fen in [
+			"k7/8/1K6/8/8/8/8/7R w - - 0 1",
+			"8/8/8/4k3/8/8/8/R3K3 b - - 0 1",
+			"K7/8/1k6/8/8/8/8/7r b - - 0 1",
+		] {
+			let board = fen.parse().unwrap();
+
+			assert_eq!(file.probe(&board), table.probe(&board), "{fen}");
+		}
+
+		fs::write(&path, MAGIC).unwrap();
+		assert!(TableFile::open(&path).is_err());
+
+		fs::remove_file(&path).unwrap();
+	}
+}
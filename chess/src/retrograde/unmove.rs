@@ -0,0 +1,69 @@
+use crate::{
+	board::{
+		bitboard::BitboardUtils, color::ColorUtils, file_rank::RankUtils, piece::PieceUtils,
+		square::SquareUtils, Bitboard, Board, Color, Square,
+	},
+	move_gen::MoveGen,
+};
+
+/// Calls `visit` with every legal position the side that just moved could have come from by a
+/// move that neither captured nor promoted. Castling and en passant are left out, as tables have
+/// neither.
+pub(crate) fn unmoves(move_gen: &MoveGen, board: &mut Board, mut visit: impl FnMut(&Board)) {
+	let mover = board.color ^ 1;
+	let king = BitboardUtils::lsb(board.pieces[board.color][PieceUtils::KING]);
+
+	for piece in PieceUtils::RANGE {
+		let mut pieces = board.pieces[mover][piece];
+
+		while pieces > 0 {
+			let to = BitboardUtils::pop_lsb(&mut pieces);
+			let mut origins = match piece {
+				PieceUtils::PAWN => pawn_origins(board, mover, to),
+				_ => move_gen.attacks(piece, mover, to, board.occupancy) & !board.occupancy,
+			};
+
+			while origins > 0 {
+				let from = BitboardUtils::pop_lsb(&mut origins);
+
+				board.remove_piece(piece, mover, to);
+				board.add_piece(piece, mover, from);
+				board.switch_color();
+
+				if !move_gen.square_attacked(board, mover, king) {
+					visit(board);
+				}
+
+				board.switch_color();
+				board.remove_piece(piece, mover, from);
+				board.add_piece(piece, mover, to);
+			}
+		}
+	}
+}
+
+/// The squares a pawn of `color` on `square` could have been pushed from.
+fn pawn_origins(board: &Board, color: Color, square: Square) -> Bitboard {
+	let (_, rank) = SquareUtils::location(square);
+	let empty = !board.occupancy;
+
+	let relative_rank = match color {
+		ColorUtils::WHITE => rank,
+		_ => RankUtils::R8 - rank,
+	};
+	let behind = |steps: usize| match color {
+		ColorUtils::WHITE => square - 8 * steps,
+		_ => square + 8 * steps,
+	};
+
+	if relative_rank <= RankUtils::R2 {
+		return BitboardUtils::EMPTY;
+	}
+
+	let single = BitboardUtils::SQUARES[behind(1)] & empty;
+
+	match single > 0 && relative_rank == RankUtils::R4 {
+		true => single | BitboardUtils::SQUARES[behind(2)] & empty,
+		false => single,
+	}
+}
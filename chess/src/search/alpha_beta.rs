@@ -0,0 +1,139 @@
+use super::{Score, SearchUtils, Searcher};
+use crate::{
+	move_gen::Move,
+	transposition::{Bound, SearchData, TableData as _},
+};
+
+impl Searcher<'_> {
+	pub(super) fn alpha_beta(
+		&mut self,
+		mut depth: u8,
+		mut alpha: Score,
+		beta: Score,
+		ply: usize,
+		pv: &mut Vec<Move>,
+	) -> Score {
+		if depth == 0 {
+			return self.quiescence(alpha, beta, ply);
+		}
+
+		self.checkup();
+
+		if self.stopped {
+			return SearchUtils::DRAW;
+		}
+
+		self.nodes += 1;
+
+		if ply >= SearchUtils::MAX_PLY {
+			return self.evaluate();
+		}
+
+		let draw = self.chess.board.halfmove_clock >= 100 || self.chess.repetitions() > 1;
+
+		if ply > 0 && draw {
+			return SearchUtils::DRAW;
+		}
+
+		let in_check = self.chess.in_check();
+
+		if in_check {
+			depth += 1;
+		}
+
+		let hash = self.chess.board.hash;
+		let tt_entry = self.chess.tt.get(hash);
+
+		if let Some(entry) = tt_entry.filter(|entry| ply > 0 && entry.depth() >= depth) {
+			let score = entry.score(ply);
+
+			let cutoff = match entry.bound() {
+				Bound::Exact => true,
+				Bound::Lower => score >= beta,
+				Bound::Upper => score <= alpha,
+			};
+
+			if cutoff {
+				pv.clear();
+				pv.extend(entry.best_move());
+
+				return score;
+			}
+		}
+
+		let pv_move = match self.follow_pv {
+			true => self.pv.get(ply).copied(),
+			false => None,
+		};
+
+		if pv_move.is_none() {
+			self.follow_pv = false;
+		}
+
+		let list = self.chess.generate_legal_moves();
+
+		if list.len() == 0 {
+			return match in_check {
+				true => -SearchUtils::MATE + ply as Score,
+				false => SearchUtils::DRAW,
+			};
+		}
+
+		if let Some(score) = self.probe_tablebase(ply) {
+			return score;
+		}
+
+		let tt_move = tt_entry.and_then(|entry| entry.best_move());
+		let original_alpha = alpha;
+		let mut best_move = None;
+
+		for m in self.order_moves(&list, pv_move, tt_move) {
+			if ply == 0 && !self.root_moves.is_empty() && !self.root_moves.contains(&m) {
+				continue;
+			}
+
+			self.chess.make_move(m);
+
+			let mut line = Vec::new();
+			let score = -self.alpha_beta(depth - 1, -beta, -alpha, ply + 1, &mut line);
+			self.chess.undo_move();
+
+			self.follow_pv = false;
+
+			if self.stopped {
+				return SearchUtils::DRAW;
+			}
+
+			if score > alpha {
+				alpha = score;
+				best_move = Some(m);
+
+				pv.clear();
+				pv.push(m);
+				pv.extend(line);
+
+				if score >= beta {
+					self.store(best_move, beta, Bound::Lower, depth, ply);
+
+					return beta;
+				}
+			}
+		}
+
+		let bound = match alpha > original_alpha {
+			true => Bound::Exact,
+			false => Bound::Upper,
+		};
+
+		self.store(best_move.or(tt_move), alpha, bound, depth, ply);
+
+		alpha
+	}
+
+	fn store(&self, best_move: Option<Move>, score: Score, bound: Bound, depth: u8, ply: usize) {
+		let tt = &self.chess.tt;
+		let data = SearchData::new(best_move, score, bound, depth, ply, tt.generation());
+
+		tt.insert(self.chess.board.hash, data);
+	}
+}
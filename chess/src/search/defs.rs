@@ -0,0 +1,54 @@
+use crate::move_gen::Move;
+use std::time::Duration;
+
+pub type Score = i32;
+
+pub struct SearchUtils;
+
+impl SearchUtils {
+	pub const MAX_PLY: usize = 128;
+	pub const INFINITY: Score = 32_000;
+	pub const MATE: Score = 31_000;
+	pub const MATE_BOUND: Score = Self::MATE - Self::MAX_PLY as Score;
+	/// The score of a tablebase win at the root, below every mate score.
+	pub const TABLEBASE_WIN: Score = Self::MATE_BOUND - Self::MAX_PLY as Score - 1;
+	pub const DRAW: Score = 0;
+}
+
+impl SearchUtils {
+	pub fn is_mate(score: Score) -> bool {
+		score.abs() >= Self::MATE_BOUND
+	}
+
+	/// Number of moves until mate, negative when the side to move is the one getting mated.
+	pub fn mate_in(score: Score) -> Option<i32> {
+		if score >= Self::MATE_BOUND {
+			Some((Self::MATE - score + 1) / 2)
+		} else if score <= -Self::MATE_BOUND {
+			Some(-(Self::MATE + score) / 2)
+		} else {
+			None
+		}
+	}
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SearchLimits {
+	pub depth: Option<u8>,
+	pub nodes: Option<u64>,
+	pub time: Option<Duration>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SearchResult {
+	pub best_move: Option<Move>,
+	pub score: Score,
+	pub depth: u8,
+	pub nodes: u64,
+	pub elapsed: Duration,
+	pub pv: Vec<Move>,
+	/// The permille of the transposition table filled by this search.
+	pub hashfull: u16,
+	/// The number of positions scored by the tablebase.
+	pub tb_hits: u64,
+}
@@ -0,0 +1,180 @@
+mod alpha_beta;
+mod defs;
+mod ordering;
+mod quiescence;
+
+use crate::{move_gen::Move, tablebase::Wdl, transposition::TranspositionTable, Chess};
+use std::{
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+	time::Instant,
+};
+
+pub use defs::*;
+
+const CHECKUP_INTERVAL: u64 = 2048;
+
+impl Chess {
+	/// Runs an iterative deepening search until `limits` are reached or `stop` is raised,
+	/// calling `report` after every completed iteration.
+	pub fn search<F>(
+		&mut self,
+		limits: SearchLimits,
+		stop: Arc<AtomicBool>,
+		report: F,
+	) -> SearchResult
+	where
+		F: FnMut(&SearchResult),
+	{
+		Searcher::new(self, limits, stop).iterate(report)
+	}
+
+	/// Replaces the transposition table used by `search` with an empty one of `bytes` bytes,
+	/// shared by all clones made afterwards.
+	pub fn set_hash_size(&mut self, bytes: usize) {
+		self.tt = Arc::new(TranspositionTable::new(bytes));
+	}
+
+	/// Forgets everything stored by earlier searches.
+	pub fn clear_hash(&self) {
+		self.tt.clear();
+	}
+}
+
+pub(crate) struct Searcher<'a> {
+	chess: &'a mut Chess,
+	limits: SearchLimits,
+	stop: Arc<AtomicBool>,
+	start: Instant,
+	nodes: u64,
+	stopped: bool,
+	can_stop: bool,
+	pv: Vec<Move>,
+	follow_pv: bool,
+	/// The root moves that keep the tablebase outcome, or empty to search every move.
+	root_moves: Vec<Move>,
+	tb_hits: u64,
+}
+
+impl<'a> Searcher<'a> {
+	fn new(chess: &'a mut Chess, limits: SearchLimits, stop: Arc<AtomicBool>) -> Self {
+		let root_moves = chess
+			.tablebase
+			.as_ref()
+			.filter(|tablebase| tablebase.contains(&chess.board))
+			.and_then(|tablebase| chess.tablebase_moves(tablebase).ok())
+			.unwrap_or_default();
+
+		Self {
+			chess,
+			limits,
+			stop,
+			start: Instant::now(),
+			nodes: 0,
+			stopped: false,
+			can_stop: false,
+			pv: Vec::new(),
+			follow_pv: false,
+			root_moves,
+			tb_hits: 0,
+		}
+	}
+
+	fn iterate<F: FnMut(&SearchResult)>(&mut self, mut report: F) -> SearchResult {
+		let max_depth = self
+			.limits
+			.depth
+			.unwrap_or(SearchUtils::MAX_PLY as u8)
+			.clamp(1, SearchUtils::MAX_PLY as u8);
+
+		let mut result = SearchResult::default();
+
+		self.chess.tt.new_search();
+
+		for depth in 1..=max_depth {
+			let mut pv = Vec::new();
+
+			self.follow_pv = true;
+
+			let score = self.alpha_beta(
+				depth,
+				-SearchUtils::INFINITY,
+				SearchUtils::INFINITY,
+				0,
+				&mut pv,
+			);
+
+			if self.stopped {
+				break;
+			}
+
+			self.pv = pv;
+			self.can_stop = true;
+
+			result = SearchResult {
+				best_move: self.pv.first().copied(),
+				score,
+				depth,
+				nodes: self.nodes,
+				elapsed: self.start.elapsed(),
+				pv: self.pv.clone(),
+				hashfull: self.chess.tt.hashfull(),
+				tb_hits: self.tb_hits,
+			};
+
+			report(&result);
+
+			let mate_found =
+				SearchUtils::mate_in(score).is_some_and(|moves| depth as i32 >= moves.abs() * 2);
+
+			if result.best_move.is_none() || mate_found {
+				break;
+			}
+		}
+
+		result
+	}
+
+	fn checkup(&mut self) {
+		if !self.can_stop || !self.nodes.is_multiple_of(CHECKUP_INTERVAL) {
+			return;
+		}
+
+		let stop_raised = self.stop.load(Ordering::Relaxed);
+		let out_of_nodes = self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes);
+		let out_of_time = self
+			.limits
+			.time
+			.is_some_and(|time| self.start.elapsed() >= time);
+
+		self.stopped = stop_raised || out_of_nodes || out_of_time;
+	}
+
+	/// The tablebase score of a position just reached by a capture or pawn move, where the WDL
+	/// tables are exact. Wins and losses that the fifty-move rule spoils score as draws.
+	fn probe_tablebase(&mut self, ply: usize) -> Option<Score> {
+		let board = &self.chess.board;
+		let tablebase = self.chess.tablebase.as_ref()?;
+
+		if ply == 0 || board.halfmove_clock != 0 || !tablebase.contains(board) {
+			return None;
+		}
+
+		let wdl = tablebase.probe_wdl_after_zeroing(board).ok()?;
+
+		self.tb_hits += 1;
+
+		Some(match wdl {
+			Wdl::Win => SearchUtils::TABLEBASE_WIN - ply as Score,
+			Wdl::Loss => -SearchUtils::TABLEBASE_WIN + ply as Score,
+			_ => SearchUtils::DRAW,
+		})
+	}
+
+	#[inline(always)]
+	fn evaluate(&self) -> Score {
+		self.chess.evaluate()
+	}
+}
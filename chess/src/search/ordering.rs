@@ -0,0 +1,62 @@
+use super::{Score, Searcher};
+use crate::{board::piece::PieceUtils, move_gen::Move, move_list::MoveList};
+
+const PV_MOVE: Score = 1_000_000;
+const TT_MOVE: Score = 500_000;
+const CAPTURE: Score = 100_000;
+const PROMOTION: Score = 90_000;
+
+// Most Valuable Victim - Least Valuable Aggressor, indexed by [victim][attacker].
+#[rustfmt::skip]
+const MVV_LVA: [[Score; PieceUtils::SIZE]; PieceUtils::SIZE] = [
+	[15, 14, 13, 12, 11, 10],
+	[25, 24, 23, 22, 21, 20],
+	[35, 34, 33, 32, 31, 30],
+	[45, 44, 43, 42, 41, 40],
+	[55, 54, 53, 52, 51, 50],
+	[ 0,  0,  0,  0,  0,  0],
+];
+
+impl Searcher<'_> {
+	pub(super) fn order_moves(
+		&self,
+		list: &MoveList,
+		pv_move: Option<Move>,
+		tt_move: Option<Move>,
+	) -> Vec<Move> {
+		let mut scored = list
+			.iter()
+			.map(|m| (Self::score_move(*m, pv_move, tt_move), *m))
+			.collect::<Vec<_>>();
+
+		scored.sort_unstable_by_key(|(score, _)| std::cmp::Reverse(*score));
+		scored.into_iter().map(|(_, m)| m).collect()
+	}
+
+	fn score_move(m: Move, pv_move: Option<Move>, tt_move: Option<Move>) -> Score {
+		if Some(m) == pv_move {
+			return PV_MOVE;
+		}
+
+		if Some(m) == tt_move {
+			return TT_MOVE;
+		}
+
+		let captured = m.captured();
+		let promoted = m.promoted();
+
+		let mut score = 0;
+
+		if captured != PieceUtils::NONE {
+			score += CAPTURE + MVV_LVA[captured][m.piece()];
+		} else if m.en_passant() {
+			score += CAPTURE + MVV_LVA[PieceUtils::PAWN][PieceUtils::PAWN];
+		}
+
+		if promoted != PieceUtils::NONE {
+			score += PROMOTION + promoted as Score;
+		}
+
+		score
+	}
+}
@@ -0,0 +1,49 @@
+use super::{Score, SearchUtils, Searcher};
+use crate::board::piece::PieceUtils;
+
+impl Searcher<'_> {
+	pub(super) fn quiescence(&mut self, mut alpha: Score, beta: Score, ply: usize) -> Score {
+		self.checkup();
+
+		if self.stopped {
+			return SearchUtils::DRAW;
+		}
+
+		self.nodes += 1;
+
+		let stand_pat = self.evaluate();
+
+		if ply >= SearchUtils::MAX_PLY || stand_pat >= beta {
+			return stand_pat.min(beta);
+		}
+
+		alpha = alpha.max(stand_pat);
+
+		let list = self.chess.generate_legal_moves();
+
+		for m in self.order_moves(&list, None, None) {
+			let is_capture = m.captured() != PieceUtils::NONE || m.en_passant();
+
+			if !is_capture && m.promoted() == PieceUtils::NONE {
+				continue;
+			}
+
+			self.chess.make_move(m);
+
+			let score = -self.quiescence(-beta, -alpha, ply + 1);
+			self.chess.undo_move();
+
+			if self.stopped {
+				return SearchUtils::DRAW;
+			}
+
+			if score >= beta {
+				return beta;
+			}
+
+			alpha = alpha.max(score);
+		}
+
+		alpha
+	}
+}
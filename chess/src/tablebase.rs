@@ -0,0 +1,391 @@
+use crate::{
+	board::{
+		bitboard::BitboardUtils, castle_right::CastleRightUtils, color::ColorUtils,
+		piece::PieceUtils, square::SquareUtils, Board, Piece,
+	},
+	move_gen::Move,
+	Chess,
+};
+use shakmaty::{Bitboard, ByColor, ByRole, CastlingMode, FromSetup, Setup};
+use shakmaty_syzygy::{SyzygyError, Tablebase as Tables};
+use std::{error::Error, fmt, io, num::NonZeroU32, ops::Neg, path::Path, sync::Arc};
+
+/// The outcome of a position for the side to move, taking the fifty-move rule into account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Wdl {
+	Loss,
+	/// A loss that the fifty-move rule turns into a draw.
+	BlessedLoss,
+	Draw,
+	/// A win that the fifty-move rule turns into a draw.
+	CursedWin,
+	Win,
+}
+
+impl From<shakmaty_syzygy::Wdl> for Wdl {
+	fn from(wdl: shakmaty_syzygy::Wdl) -> Self {
+		match wdl {
+			shakmaty_syzygy::Wdl::Loss => Self::Loss,
+			shakmaty_syzygy::Wdl::BlessedLoss => Self::BlessedLoss,
+			shakmaty_syzygy::Wdl::Draw => Self::Draw,
+			shakmaty_syzygy::Wdl::CursedWin => Self::CursedWin,
+			shakmaty_syzygy::Wdl::Win => Self::Win,
+		}
+	}
+}
+
+impl Neg for Wdl {
+	type Output = Self;
+
+	/// The outcome for the opponent.
+	fn neg(self) -> Self {
+		match self {
+			Self::Loss => Self::Win,
+			Self::BlessedLoss => Self::CursedWin,
+			Self::Draw => Self::Draw,
+			Self::CursedWin => Self::BlessedLoss,
+			Self::Win => Self::Loss,
+		}
+	}
+}
+
+/// Syzygy endgame tablebases read from `.rtbw` and `.rtbz` files. The files are opened lazily
+/// on the first probe that needs them.
+pub struct Tablebase {
+	tables: Tables<shakmaty::Chess>,
+}
+
+impl Default for Tablebase {
+	fn default() -> Self {
+		Self {
+			tables: Tables::new(),
+		}
+	}
+}
+
+impl fmt::Debug for Tablebase {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Tablebase")
+			.field("max_pieces", &self.max_pieces())
+			.finish()
+	}
+}
+
+impl Tablebase {
+	/// Adds the tables of a directory, returning the number of files found.
+	pub fn add_directory(&mut self, path: impl AsRef<Path>) -> io::Result<usize> {
+		self.tables.add_directory(path)
+	}
+
+	/// The largest number of pieces, kings included, of the added tables.
+	pub fn max_pieces(&self) -> usize {
+		self.tables.max_pieces()
+	}
+
+	/// The outcome of the position for the side to move. Probing requires the DTZ tables as
+	/// well, since the fifty-move rule depends on the halfmove clock. Distances the tables round
+	/// are taken as exact, which holds along the tablebase line from a capture or pawn move.
+	pub fn probe_wdl(&self, board: &Board) -> Result<Wdl, TablebaseError> {
+		let position = self.position(board)?;
+
+		Ok(self.tables.probe_wdl(&position)?.after_zeroing().into())
+	}
+
+	/// The outcome of a position just reached by a capture or pawn move, which only requires
+	/// the WDL tables.
+	pub fn probe_wdl_after_zeroing(&self, board: &Board) -> Result<Wdl, TablebaseError> {
+		let position = self.position(board)?;
+
+		Ok(self.tables.probe_wdl_after_zeroing(&position)?.into())
+	}
+
+	/// The distance in plies to the next capture or pawn move that keeps the best outcome,
+	/// positive when the side to move wins and negative when it loses. A few tables round the
+	/// distance by one ply.
+	pub fn probe_dtz(&self, board: &Board) -> Result<i32, TablebaseError> {
+		let position = self.position(board)?;
+		let dtz = self.tables.probe_dtz(&position)?;
+
+		Ok(dtz.ignore_rounding().0)
+	}
+
+	/// Whether the position has few enough pieces to be probed.
+	pub fn contains(&self, board: &Board) -> bool {
+		let pieces = board.occupancy.count_ones() as usize;
+
+		pieces <= self.max_pieces() && board.castle_rights == CastleRightUtils::NONE
+	}
+
+	/// Builds the position for the tables straight from the board's bitboards.
+	fn position(&self, board: &Board) -> Result<shakmaty::Chess, TablebaseError> {
+		if board.occupancy.count_ones() as usize > self.max_pieces() {
+			return Err(TablebaseError::TooManyPieces);
+		}
+
+		let invalid = |error: &dyn fmt::Display| TablebaseError::InvalidPosition(error.to_string());
+
+		let role = |piece: Piece| {
+			Bitboard(
+				board.pieces[ColorUtils::WHITE][piece] | board.pieces[ColorUtils::BLACK][piece],
+			)
+		};
+		let by_role = ByRole {
+			pawn: role(PieceUtils::PAWN),
+			knight: role(PieceUtils::KNIGHT),
+			bishop: role(PieceUtils::BISHOP),
+			rook: role(PieceUtils::ROOK),
+			queen: role(PieceUtils::QUEEN),
+			king: role(PieceUtils::KING),
+		};
+		let by_color = ByColor {
+			white: Bitboard(board.occupancy_color[ColorUtils::WHITE]),
+			black: Bitboard(board.occupancy_color[ColorUtils::BLACK]),
+		};
+
+		let castling_rights = [
+			(CastleRightUtils::WHITE_KING, SquareUtils::H1),
+			(CastleRightUtils::WHITE_QUEEN, SquareUtils::A1),
+			(CastleRightUtils::BLACK_KING, SquareUtils::H8),
+			(CastleRightUtils::BLACK_QUEEN, SquareUtils::A8),
+		]
+		.into_iter()
+		.filter(|&(castle_right, _)| board.castle_rights & castle_right != 0)
+		.fold(BitboardUtils::EMPTY, |rooks, (_, square)| {
+			rooks | BitboardUtils::SQUARES[square]
+		});
+
+		let setup = Setup {
+			board: shakmaty::Board::try_from_bitboards(by_role, by_color)
+				.map_err(|error| invalid(&error))?,
+			turn: match board.color {
+				ColorUtils::WHITE => shakmaty::Color::White,
+				_ => shakmaty::Color::Black,
+			},
+			castling_rights: Bitboard(castling_rights),
+			ep_square: board
+				.en_passant
+				.map(|square| shakmaty::Square::new(square as u32)),
+			halfmoves: board.halfmove_clock as u32,
+			fullmoves: NonZeroU32::new(board.fullmove_number as u32).unwrap_or(NonZeroU32::MIN),
+			..Setup::empty()
+		};
+
+		shakmaty::Chess::from_setup(setup, CastlingMode::Standard).map_err(|error| invalid(&error))
+	}
+}
+
+impl Chess {
+	/// Sets the tablebase probed by `search`, shared by all clones made afterwards.
+	pub fn set_tablebase(&mut self, tablebase: Option<Tablebase>) {
+		self.tablebase = tablebase.map(Arc::new);
+	}
+
+	/// The legal moves that keep the best tablebase outcome of the position. Among winning moves
+	/// only those that still win under the fifty-move rule are kept, so a search restricted to
+	/// them makes progress.
+	pub fn tablebase_moves(&self, tablebase: &Tablebase) -> Result<Vec<Move>, TablebaseError> {
+		let mut chess = self.position();
+		let mut moves = Vec::new();
+
+		for m in self.generate_legal_moves().iter().copied() {
+			chess.make_move(m);
+
+			let outcome = tablebase_outcome_after(&chess, tablebase);
+
+			chess.undo_move();
+			moves.push((m, outcome?));
+		}
+
+		let best = moves.iter().map(|(_, outcome)| *outcome).max();
+
+		Ok(moves
+			.into_iter()
+			.filter(|(_, outcome)| Some(*outcome) == best)
+			.map(|(m, _)| m)
+			.collect())
+	}
+}
+
+/// The outcome for the side that just moved.
+fn tablebase_outcome_after(chess: &Chess, tablebase: &Tablebase) -> Result<Wdl, TablebaseError> {
+	if chess.generate_legal_moves().len() == 0 {
+		return Ok(match chess.in_check() {
+			true => Wdl::Win,
+			false => Wdl::Draw,
+		});
+	}
+
+	Ok(-tablebase.probe_wdl(&chess.board)?)
+}
+
+#[derive(Debug)]
+pub enum TablebaseError {
+	/// The position has more pieces than the largest table.
+	TooManyPieces,
+	/// The position cannot be converted for probing.
+	InvalidPosition(String),
+	Probe(SyzygyError),
+}
+
+impl fmt::Display for TablebaseError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::TooManyPieces => write!(f, "too many pieces for the tablebase"),
+			Self::InvalidPosition(error) => write!(f, "invalid position: {error}"),
+			Self::Probe(error) => write!(f, "{error}"),
+		}
+	}
+}
+
+impl Error for TablebaseError {}
+
+impl From<SyzygyError> for TablebaseError {
+	fn from(error: SyzygyError) -> Self {
+		Self::Probe(error)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		retrograde::{Dtm, Solver},
+		search::{Score, SearchLimits, SearchUtils},
+	};
+	use std::sync::atomic::AtomicBool;
+
+	#[test]
+	fn outcomes() {
+		assert!(Wdl::Loss < Wdl::BlessedLoss && Wdl::CursedWin < Wdl::Win);
+
+		for wdl in [
+			Wdl::Loss,
+			Wdl::BlessedLoss,
+			Wdl::Draw,
+			Wdl::CursedWin,
+			Wdl::Win,
+		] {
+			assert_eq!(-(-wdl), wdl);
+		}
+
+		assert_eq!(-Wdl::CursedWin, Wdl::BlessedLoss);
+	}
+
+	#[test]
+	fn probe_without_tables() {
+		let tablebase = Tablebase::default();
+		let board = Board::from("8/8/8/8/8/3k4/8/3KQ3 w - - 0 1");
+
+		assert!(!tablebase.contains(&board));
+		assert!(matches!(
+			tablebase.probe_wdl(&board),
+			Err(TablebaseError::TooManyPieces)
+		));
+	}
+
+	/// The KQvK and KRvK tables of the 3-4-5 piece Syzygy set, bundled as test data.
+	fn bundled() -> Tablebase {
+		let mut tablebase = Tablebase::default();
+		let files = tablebase
+			.add_directory(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/syzygy"))
+			.unwrap();
+
+		assert_eq!(files, 4);
+		assert_eq!(tablebase.max_pieces(), 3);
+
+		tablebase
+	}
+
+	#[test]
+	fn probe_bundled_tables() {
+		let tablebase = bundled();
+
+		let white = Board::from("8/8/8/8/8/3k4/8/3KQ3 w - - 0 1");
+		let black = Board::from("8/8/8/8/8/3k4/8/3KQ3 b - - 0 1");
+
+		assert!(tablebase.contains(&white));
+		assert_eq!(tablebase.probe_wdl(&white).unwrap(), Wdl::Win);
+		assert_eq!(tablebase.probe_wdl_after_zeroing(&white).unwrap(), Wdl::Win);
+		assert_eq!(tablebase.probe_wdl(&black).unwrap(), Wdl::Loss);
+		assert!(tablebase.probe_dtz(&white).unwrap() > 0);
+		assert!(tablebase.probe_dtz(&black).unwrap() < 0);
+
+		// Black wins with the colours swapped, and the lone king takes a hanging rook.
+		let black_wins = Board::from("8/8/8/8/8/3K4/8/3kq3 b - - 0 1");
+		let hanging = Board::from("8/8/8/8/8/8/1kR5/4K3 b - - 0 1");
+
+		assert_eq!(tablebase.probe_wdl(&black_wins).unwrap(), Wdl::Win);
+		assert_eq!(tablebase.probe_wdl(&hanging).unwrap(), Wdl::Draw);
+		assert_eq!(tablebase.probe_dtz(&hanging).unwrap(), 0);
+
+		let four_pieces = Board::from("8/8/8/8/8/3k4/3p4/3KQ3 w - - 0 1");
+
+		assert!(!tablebase.contains(&four_pieces));
+		assert!(matches!(
+			tablebase.probe_wdl(&four_pieces),
+			Err(TablebaseError::TooManyPieces)
+		));
+	}
+
+	/// The tables agree with the retrograde solver on whether positions are won.
+	#[test]
+	fn bundled_tables_match_retrograde_tables() {
+		let tablebase = bundled();
+		let mut solver = Solver::default();
+
+		for material in ["KQvK", "KRvK"] {
+			solver.solve(&material.parse().unwrap());
+		}
+
+		for fen in [
+			"8/8/8/8/8/3k4/8/3KQ3 w - - 0 1",
+			"8/8/8/8/8/3k4/8/3KQ3 b - - 0 1",
+			"k7/8/1K6/8/8/8/7Q/8 w - - 0 1",
+			"k7/2Q5/1K6/8/8/8/8/8 b - - 0 1",
+			"8/8/8/4k3/8/8/8/R3K3 w - - 0 1",
+			"8/8/8/4k3/8/8/8/R3K3 b - - 0 1",
+			"8/8/8/8/8/8/1kR5/4K3 b - - 0 1",
+			"8/8/8/8/8/8/1kr5/4K3 w - - 0 1",
+		] {
+			let board = Board::from(fen);
+			let wdl = match solver.probe(&board).unwrap() {
+				Dtm::Win(_) => Wdl::Win,
+				Dtm::Draw => Wdl::Draw,
+				Dtm::Loss(_) => Wdl::Loss,
+			};
+
+			assert_eq!(tablebase.probe_wdl(&board).unwrap(), wdl, "{fen}");
+		}
+	}
+
+	#[test]
+	fn search_with_tablebase() {
+		// Qe3+ loses the queen to the king, so only the other moves are searched.
+		let mut chess = Chess::try_from("8/8/8/8/8/3k4/8/3KQ3 w - - 0 1").unwrap();
+		let tablebase = bundled();
+		let moves = chess.tablebase_moves(&tablebase).unwrap();
+
+		assert!(!moves.is_empty());
+		assert!(!moves.contains(&chess.parse_uci_move("e1e3").unwrap()));
+
+		chess.set_tablebase(Some(tablebase));
+
+		let limits = SearchLimits {
+			depth: Some(2),
+			..SearchLimits::default()
+		};
+		let result = chess.search(limits, Arc::new(AtomicBool::new(false)), |_| {});
+
+		assert!(moves.contains(&result.best_move.unwrap()));
+
+		// Taking the knight reaches KQvK, which is scored from the tables.
+		let mut chess = Chess::try_from("4k3/8/8/8/8/8/3n4/3QK3 w - - 0 1").unwrap();
+		chess.set_tablebase(Some(bundled()));
+
+		let result = chess.search(limits, Arc::new(AtomicBool::new(false)), |_| {});
+
+		assert!(result.tb_hits > 0);
+		assert!(result.score > SearchUtils::TABLEBASE_WIN - SearchUtils::MAX_PLY as Score);
+		assert_eq!(result.best_move.unwrap().to(), SquareUtils::D2);
+	}
+}
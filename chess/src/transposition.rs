@@ -1,96 +1,202 @@
-use crate::board::zobrist::ZobristHash;
+use crate::{
+	board::zobrist::ZobristHash,
+	move_gen::Move,
+	search::{Score, SearchUtils},
+};
+use std::{
+	fmt,
+	marker::PhantomData,
+	sync::atomic::{AtomicU64, AtomicU8, Ordering},
+};
 
 const ENTRIES_PER_BUCKET: usize = 4;
-const HIGH_FOUR_BYTES: u64 = 0xFF_FF_FF_FF_00_00_00_00;
-const LOW_FOUR_BYTES: u64 = 0x00_00_00_00_FF_FF_FF_FF;
-const SHIFT_TO_LOWER: u64 = 32;
+const GENERATION_MASK: u8 = 0x3F;
+const AGE_WEIGHT: i32 = 8;
+const HASHFULL_SAMPLE: usize = 250;
 
-pub trait Depth {
+/// Data that fits in the 64 bits of a table entry. A packed value of zero marks an empty entry.
+pub trait TableData: Copy {
+	fn pack(self) -> u64;
+	fn unpack(data: u64) -> Self;
 	fn depth(&self) -> u8;
+
+	/// The search that stored the entry, for tables that age their entries.
+	fn generation(&self) -> u8 {
+		0
+	}
+}
+
+/// An entry stores its key XORed with its data, so a torn write by another thread fails
+/// verification instead of returning data for the wrong position.
+#[derive(Default)]
+pub struct Entry {
+	key: AtomicU64,
+	data: AtomicU64,
 }
 
-#[derive(Default, Clone)]
-pub struct Entry<V> {
-	data: V,
-	verification: u32,
+impl Entry {
+	#[inline(always)]
+	fn load(&self) -> (ZobristHash, u64) {
+		let data = self.data.load(Ordering::Relaxed);
+		let key = self.key.load(Ordering::Relaxed) ^ data;
+
+		(key, data)
+	}
+
+	#[inline(always)]
+	fn store(&self, hash: ZobristHash, data: u64) {
+		self.key.store(hash ^ data, Ordering::Relaxed);
+		self.data.store(data, Ordering::Relaxed);
+	}
 }
 
-#[derive(Default, Clone)]
-pub struct Bucket<V>([Entry<V>; ENTRIES_PER_BUCKET]);
+#[derive(Default)]
+#[repr(align(64))]
+pub struct Bucket([Entry; ENTRIES_PER_BUCKET]);
 
-impl<V: Depth> Bucket<V> {
-	pub fn insert(&mut self, verification: u32, data: V) {
-		let mut idx_lowest_depth = 0;
+impl Bucket {
+	/// Overwrites the entry of the same position if there is one, or else the entry that is
+	/// the shallowest once older generations are penalized.
+	fn insert<V: TableData>(&self, hash: ZobristHash, value: V, generation: u8) {
+		let mut replace = 0;
+		let mut lowest_worth = i32::MAX;
 
 		for (idx, entry) in self.0.iter().enumerate() {
-			if entry.data.depth() < data.depth() {
-				idx_lowest_depth = idx;
+			let (key, data) = entry.load();
+
+			if data == 0 || key == hash {
+				replace = idx;
+				break;
 			}
-		}
 
-		self.0[idx_lowest_depth] = Entry { data, verification };
-	}
+			let old = V::unpack(data);
+			let age = generation.wrapping_sub(old.generation()) & GENERATION_MASK;
+			let worth = old.depth() as i32 - AGE_WEIGHT * age as i32;
 
-	pub fn get(&self, verification: u32) -> Option<&V> {
-		for entry in self.0.iter() {
-			if entry.verification == verification {
-				return Some(&entry.data);
+			if worth < lowest_worth {
+				lowest_worth = worth;
+				replace = idx;
 			}
 		}
 
-		None
+		self.0[replace].store(hash, value.pack());
+	}
+
+	fn get<V: TableData>(&self, hash: ZobristHash) -> Option<V> {
+		self.0.iter().find_map(|entry| match entry.load() {
+			(key, data) if data != 0 && key == hash => Some(V::unpack(data)),
+			_ => None,
+		})
 	}
 }
 
-#[derive(Default)]
+/// A lock-free hash table shared by reference between threads.
 pub struct TranspositionTable<V> {
-	entries: Vec<Bucket<V>>,
+	buckets: Vec<Bucket>,
+	generation: AtomicU8,
+	data: PhantomData<V>,
+}
+
+impl<V: TableData> Default for TranspositionTable<V> {
+	fn default() -> Self {
+		Self::new(0)
+	}
 }
 
-impl<V: Depth + Default + Clone> TranspositionTable<V> {
+impl<V> fmt::Debug for TranspositionTable<V> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("TranspositionTable")
+			.field("buckets", &self.buckets.len())
+			.field("generation", &self.generation)
+			.finish()
+	}
+}
+
+impl<V: TableData> TranspositionTable<V> {
 	pub fn new(bytes: usize) -> Self {
-		let total_buckets = bytes / std::mem::size_of::<Bucket<V>>();
+		let total_buckets = (bytes / std::mem::size_of::<Bucket>()).max(1);
 
 		Self {
-			entries: vec![Bucket::default(); total_buckets],
+			buckets: (0..total_buckets).map(|_| Bucket::default()).collect(),
+			generation: AtomicU8::new(0),
+			data: PhantomData,
 		}
 	}
 
-	pub fn insert(&mut self, hash: ZobristHash, data: V) {
-		let index = self.calculate_index(hash);
-		let verification = self.calculate_verification(hash);
+	#[inline(always)]
+	pub fn insert(&self, hash: ZobristHash, data: V) {
+		self.bucket(hash).insert(hash, data, self.generation());
+	}
 
-		self.entries[index].insert(verification, data);
+	#[inline(always)]
+	pub fn get(&self, hash: ZobristHash) -> Option<V> {
+		self.bucket(hash).get(hash)
 	}
 
-	pub fn get(&self, hash: ZobristHash) -> Option<&V> {
-		let index = self.calculate_index(hash);
-		let verification = self.calculate_verification(hash);
+	pub fn generation(&self) -> u8 {
+		self.generation.load(Ordering::Relaxed)
+	}
+
+	/// Starts a new generation, so the entries of earlier searches are replaced first.
+	pub fn new_search(&self) {
+		let generation = self.generation().wrapping_add(1) & GENERATION_MASK;
 
-		self.entries[index].get(verification)
+		self.generation.store(generation, Ordering::Relaxed);
 	}
-}
 
-impl<V> TranspositionTable<V> {
-	fn calculate_index(&self, hash: ZobristHash) -> usize {
-		let key = (hash & HIGH_FOUR_BYTES) >> SHIFT_TO_LOWER;
-		let total = self.entries.len() as u64;
+	pub fn clear(&self) {
+		for entry in self.buckets.iter().flat_map(|bucket| &bucket.0) {
+			entry.store(0, 0);
+		}
+
+		self.generation.store(0, Ordering::Relaxed);
+	}
+
+	/// The permille of sampled entries filled during the current generation, as reported by UCI
+	/// `hashfull`.
+	pub fn hashfull(&self) -> u16 {
+		let generation = self.generation();
+		let sample = self.buckets.iter().take(HASHFULL_SAMPLE);
+		let total = sample.len() * ENTRIES_PER_BUCKET;
+
+		let used = sample
+			.flat_map(|bucket| &bucket.0)
+			.filter(|entry| {
+				let (_, data) = entry.load();
+
+				data != 0 && V::unpack(data).generation() == generation
+			})
+			.count();
 
-		(key % total) as usize
+		(used * 1000 / total) as u16
 	}
 
-	fn calculate_verification(&self, hash: ZobristHash) -> u32 {
-		(hash & LOW_FOUR_BYTES) as u32
+	#[inline(always)]
+	fn bucket(&self, hash: ZobristHash) -> &Bucket {
+		let index = (hash as u128 * self.buckets.len() as u128) >> 64;
+
+		&self.buckets[index as usize]
 	}
 }
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Copy)]
 pub struct PerftData {
 	depth: u8,
 	nodes: usize,
 }
 
-impl Depth for PerftData {
+impl TableData for PerftData {
+	fn pack(self) -> u64 {
+		(self.nodes as u64) << 8 | self.depth as u64
+	}
+
+	fn unpack(data: u64) -> Self {
+		Self {
+			depth: data as u8,
+			nodes: (data >> 8) as usize,
+		}
+	}
+
 	fn depth(&self) -> u8 {
 		self.depth
 	}
@@ -105,3 +211,101 @@ impl PerftData {
 		self.nodes
 	}
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+	/// The score is exact.
+	Exact = 1,
+	/// The search failed high, so the score is at least this.
+	Lower = 2,
+	/// The search failed low, so the score is at most this.
+	Upper = 3,
+}
+
+/// The outcome of searching a position to some depth. Mate scores are stored relative to the
+/// position rather than the root, so they stay correct when reached through another path.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchData {
+	best_move: Option<Move>,
+	score: Score,
+	bound: Bound,
+	depth: u8,
+	generation: u8,
+}
+
+impl TableData for SearchData {
+	fn pack(self) -> u64 {
+		let m = self.best_move.map_or(0, |m| m.0 as u64) & 0xFF_FF_FF;
+
+		m | (self.score as i16 as u16 as u64) << 24
+			| (self.bound as u64) << 40
+			| (self.depth as u64) << 42
+			| ((self.generation & GENERATION_MASK) as u64) << 50
+	}
+
+	fn unpack(data: u64) -> Self {
+		let m = (data & 0xFF_FF_FF) as usize;
+
+		Self {
+			best_move: (m != 0).then_some(Move(m)),
+			score: (data >> 24) as u16 as i16 as Score,
+			bound: match (data >> 40) & 0x3 {
+				1 => Bound::Exact,
+				2 => Bound::Lower,
+				_ => Bound::Upper,
+			},
+			depth: (data >> 42) as u8,
+			generation: (data >> 50) as u8 & GENERATION_MASK,
+		}
+	}
+
+	fn depth(&self) -> u8 {
+		self.depth
+	}
+
+	fn generation(&self) -> u8 {
+		self.generation
+	}
+}
+
+impl SearchData {
+	pub fn new(
+		best_move: Option<Move>,
+		score: Score,
+		bound: Bound,
+		depth: u8,
+		ply: usize,
+		generation: u8,
+	) -> Self {
+		let score = match score {
+			score if score >= SearchUtils::MATE_BOUND => score + ply as Score,
+			score if score <= -SearchUtils::MATE_BOUND => score - ply as Score,
+			score => score,
+		};
+
+		Self {
+			best_move,
+			score,
+			bound,
+			depth,
+			generation,
+		}
+	}
+
+	pub fn best_move(&self) -> Option<Move> {
+		self.best_move
+	}
+
+	/// The score relative to the root, `ply` moves below it.
+	pub fn score(&self, ply: usize) -> Score {
+		match self.score {
+			score if score >= SearchUtils::MATE_BOUND => score - ply as Score,
+			score if score <= -SearchUtils::MATE_BOUND => score + ply as Score,
+			score => score,
+		}
+	}
+
+	pub fn bound(&self) -> Bound {
+		self.bound
+	}
+}
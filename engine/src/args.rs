@@ -1,10 +1,23 @@
+use crate::divide::{self, Divide, ReferenceEngine};
 use chess::{
 	board::{color::ColorUtils, pieces::BitboardPiecesUtils, Board, Color},
 	move_gen::MoveGen,
 	Chess,
 };
+use chess::{
+	book::BookBuilder,
+	epd::Epd,
+	pgn::PgnReader,
+	retrograde::{Dtm, Material, Solver, TableFile},
+};
 use clap::{command, Parser, Subcommand, ValueEnum};
-use std::time::Instant;
+use std::{
+	fmt, fs,
+	io::{BufReader, BufWriter, Write},
+	path::{Path, PathBuf},
+	process,
+	time::{Duration, Instant},
+};
 
 #[derive(Debug, Parser)]
 #[command(version)]
@@ -18,7 +31,8 @@ pub enum Command {
 	/// Display the board state
 	Display {
 		/// The FEN string to display
-		fen: Option<String>,
+		#[arg(value_parser = Board::from_fen)]
+		fen: Option<Board>,
 		/// Display individual bitboards
 		#[arg(short, long)]
 		bitboards: bool,
@@ -29,7 +43,33 @@ pub enum Command {
 		depth: u8,
 		/// The FEN string to test
 		#[arg(default_value = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")]
-		fen: String,
+		fen: Chess,
+		/// Use multi-threading, e.g. 4
+		#[arg(short, long, default_value = "1")]
+		threads: usize,
+		/// Use hashing, e.g. 1024kb, 1024mb or 1gb
+		#[arg(long)]
+		hash: Option<String>,
+		/// Compare against a reference divide output file of `move: count` lines
+		#[arg(long)]
+		compare: Option<PathBuf>,
+		/// A reference engine supporting `go perft`, used to descend into mismatching moves
+		#[arg(long)]
+		reference_engine: Option<String>,
+		/// Collect captures, castles, checks, checkmates, ... at every depth, without hashing
+		#[arg(long)]
+		stats: bool,
+		/// Repeat the test with 1 up to --threads threads and compare their speed
+		#[arg(long)]
+		scaling: bool,
+	},
+	/// Runs the perft test suite of an EPD file with D1, D2, ... node counts
+	PerftSuite {
+		/// The EPD file to test
+		file: PathBuf,
+		/// Only test depths up to this one
+		#[arg(short, long)]
+		depth: Option<u8>,
 		/// Use multi-threading, e.g. 4
 		#[arg(short, long, default_value = "1")]
 		threads: usize,
@@ -37,6 +77,19 @@ pub enum Command {
 		#[arg(long)]
 		hash: Option<String>,
 	},
+	/// Works with Polyglot opening books
+	Book {
+		#[command(subcommand)]
+		command: BookCommand,
+	},
+	/// Solves and probes endgame tables by retrograde analysis
+	Retrograde {
+		#[command(subcommand)]
+		command: RetrogradeCommand,
+	},
+
+	/// Communicate with a GUI through the Universal Chess Interface
+	Uci,
 
 	#[cfg(debug_assertions)]
 	/// Generate magic bitboards
@@ -46,6 +99,46 @@ pub enum Command {
 	},
 }
 
+#[derive(Debug, Subcommand)]
+pub enum BookCommand {
+	/// Builds a book from the games of a PGN file
+	Build {
+		/// The PGN file to read
+		#[arg(long)]
+		pgn: PathBuf,
+		/// The book file to write
+		#[arg(long)]
+		out: PathBuf,
+		/// Only record the first moves of each game, counted in plies
+		#[arg(long, default_value = "30")]
+		max_ply: usize,
+		/// Leave out moves played in fewer games
+		#[arg(long, default_value = "5")]
+		min_games: u32,
+	},
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RetrogradeCommand {
+	/// Solves a material, e.g. KQvKR, and writes its table
+	Solve {
+		/// The pieces of both sides, white's first
+		material: Material,
+		/// The table file to write
+		#[arg(long)]
+		out: PathBuf,
+	},
+	/// Prints the distance to mate of a position
+	Probe {
+		/// The table file to read
+		#[arg(long)]
+		table: PathBuf,
+		/// The FEN string to probe
+		#[arg(value_parser = |fen: &str| Board::from_fen(fen).map(Box::new))]
+		fen: Box<Board>,
+	},
+}
+
 #[derive(Debug, Clone, ValueEnum)]
 pub enum MagicPiece {
 	#[value(alias = "b", alias = "B")]
@@ -54,11 +147,14 @@ pub enum MagicPiece {
 	Rook,
 }
 
-pub fn display(fen: Option<String>, bitboards: bool) {
-	let board = match fen {
-		Some(fen) => Board::from(fen.as_str()),
-		None => Board::default(),
-	};
+pub fn display(fen: Option<Board>, bitboards: bool) {
+	let board = fen.unwrap_or_default();
+
+	if let Err(issues) = board.validate(&MoveGen::default()) {
+		for issue in issues {
+			println!("\x1b[33m\x1b[1mWarning:\x1b[0m {issue}");
+		}
+	}
 
 	match bitboards {
 		false => println!("{board}"),
@@ -71,18 +167,374 @@ pub fn display(fen: Option<String>, bitboards: bool) {
 	}
 }
 
-pub fn perft(depth: u8, fen: String, threads: usize, hash: Option<String>) {
-	let mut chess = Chess::from(fen.as_str());
+pub fn perft(depth: u8, mut chess: Chess, threads: usize, hash: Option<String>) {
+	let bytes = hash.and_then(to_bytes);
+	let report = chess.perft_with_progress(depth, threads, bytes, |m, nodes| {
+		println!("{m}: {nodes}");
+	});
 
-	let (nodes, elapsed) = chess.perft(depth, threads, hash.and_then(to_bytes));
+	if report.threads < threads {
+		println!(
+			"\x1b[33m\x1b[1mWarning:\x1b[0m Number of threads exceeds cores. Used {}.",
+			report.threads
+		);
+	}
+
+	println!("\nTotal Time (ms)\t: {}", report.elapsed.as_millis());
+	println!("Nodes searched\t: {}", report.nodes);
+	println!("Nodes/second\t: {}", report.nodes_per_second());
+
+	if bytes.is_some() {
+		println!(
+			"TT hits\t\t: {}/{} ({:.1}%)",
+			report.tt.hits,
+			report.tt.probes,
+			report.tt.hit_rate() * 100.0
+		);
+	}
+}
+
+pub fn perft_scaling(depth: u8, mut chess: Chess, threads: usize, hash: Option<String>) {
+	let bytes = hash.and_then(to_bytes);
+	let mut single_thread = None;
+
+	println!("Threads  Time (ms)  Nodes/second  Speedup");
+
+	for num_threads in 1..=threads.max(1) {
+		let report = chess.perft(depth, num_threads, bytes);
+
+		if report.threads < num_threads {
+			println!(
+				"\x1b[33m\x1b[1mWarning:\x1b[0m Only {} cores available.",
+				report.threads
+			);
+			break;
+		}
+
+		let elapsed = report.elapsed.as_secs_f64();
+		let speedup = *single_thread.get_or_insert(elapsed) / elapsed.max(1e-3);
+
+		println!(
+			"{num_threads:>7}  {:>9}  {:>12}  {speedup:>6.2}x",
+			report.elapsed.as_millis(),
+			report.nodes_per_second()
+		);
+	}
+}
+
+pub fn perft_stats(depth: u8, mut chess: Chess, threads: usize) {
+	let (stats, elapsed) = chess.perft_stats(depth, threads);
+
+	let headers = [
+		"Depth",
+		"Nodes",
+		"Captures",
+		"E.p.",
+		"Castles",
+		"Promotions",
+		"Checks",
+		"Discovered",
+		"Double",
+		"Checkmates",
+	];
+
+	let rows = stats
+		.iter()
+		.enumerate()
+		.map(|(index, stats)| {
+			[
+				index + 1,
+				stats.nodes,
+				stats.captures,
+				stats.en_passant,
+				stats.castles,
+				stats.promotions,
+				stats.checks,
+				stats.discovered_checks,
+				stats.double_checks,
+				stats.checkmates,
+			]
+			.map(|value| value.to_string())
+		})
+		.collect::<Vec<_>>();
 
-	let nodes_per_seconds = (nodes as f64 / (elapsed as f64 / 1000f64)).floor();
+	let mut widths = headers.map(str::len);
 
-	println!("\nTotal Time (ms)\t: {elapsed}");
+	for row in &rows {
+		for (width, cell) in widths.iter_mut().zip(row) {
+			*width = (*width).max(cell.len());
+		}
+	}
+
+	let line = |cells: Vec<&str>| {
+		cells
+			.iter()
+			.zip(widths)
+			.map(|(cell, width)| format!("{cell:>width$}"))
+			.collect::<Vec<_>>()
+			.join("  ")
+	};
+
+	println!("{}", line(headers.to_vec()));
+
+	for row in &rows {
+		println!("{}", line(row.iter().map(String::as_str).collect()));
+	}
+
+	let nodes = stats.iter().map(|stats| stats.nodes).sum::<usize>();
+	let nodes_per_seconds = (nodes as f64 / elapsed.as_secs_f64().max(1e-3)) as u64;
+
+	println!("\nTotal Time (ms)\t: {}", elapsed.as_millis());
 	println!("Nodes searched\t: {nodes}");
 	println!("Nodes/second\t: {nodes_per_seconds}");
 }
 
+pub fn perft_compare(
+	depth: u8,
+	chess: Chess,
+	threads: usize,
+	hash: Option<String>,
+	compare: Option<PathBuf>,
+	reference_engine: Option<String>,
+) {
+	let result = (|| {
+		let mut engine = reference_engine
+			.as_deref()
+			.map(ReferenceEngine::spawn)
+			.transpose()?;
+
+		let reference = match (compare, engine.as_mut()) {
+			(Some(file), _) => divide::parse(&fs::read_to_string(file)?),
+			(None, Some(engine)) => engine.divide(&chess.board.to_fen(), depth)?,
+			(None, None) => Divide::default(),
+		};
+
+		divide::compare(
+			chess,
+			depth,
+			threads,
+			hash.and_then(to_bytes),
+			reference,
+			engine,
+		)
+	})();
+
+	match result {
+		Ok(true) => {}
+		Ok(false) => process::exit(1),
+		Err(error) => {
+			println!("\x1b[31m\x1b[1mError:\x1b[0m {error}");
+			process::exit(1);
+		}
+	}
+}
+
+pub fn perft_suite(file: PathBuf, max_depth: Option<u8>, threads: usize, hash: Option<String>) {
+	let text = match fs::read_to_string(&file) {
+		Ok(text) => text,
+		Err(error) => {
+			println!("\x1b[31m\x1b[1mError:\x1b[0m {}: {error}", file.display());
+			process::exit(1);
+		}
+	};
+
+	let bytes = hash.and_then(to_bytes);
+	let mut chess = Chess::default();
+
+	let mut passed = 0;
+	let mut failed = 0;
+	let mut skipped = 0;
+	let mut total_nodes = 0;
+	let mut total_elapsed = Duration::ZERO;
+
+	for (index, line) in text.lines().enumerate() {
+		let line = line.trim();
+
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+
+		let epd = match line.parse::<Epd>() {
+			Ok(epd) => epd,
+			Err(error) => {
+				println!("\x1b[31m\x1b[1mFAIL\x1b[0m line {}: {error}", index + 1);
+				failed += 1;
+				continue;
+			}
+		};
+
+		let fen = epd.board.to_fen();
+		let Some((mismatches, nodes, elapsed)) =
+			suite_entry(&mut chess, &epd, max_depth, threads, bytes)
+		else {
+			println!("\x1b[33m\x1b[1mSKIP\x1b[0m {fen}: no perft count to check");
+			skipped += 1;
+			continue;
+		};
+
+		total_nodes += nodes;
+		total_elapsed += elapsed;
+
+		match mismatches.is_empty() {
+			true => {
+				println!("\x1b[32m\x1b[1mPASS\x1b[0m {fen}");
+				passed += 1;
+			}
+			false => {
+				println!("\x1b[31m\x1b[1mFAIL\x1b[0m {fen}");
+
+				for mismatch in mismatches {
+					println!("\t{mismatch}");
+				}
+
+				failed += 1;
+			}
+		}
+	}
+
+	let nodes_per_seconds = (total_nodes as f64 / total_elapsed.as_secs_f64().max(1e-3)) as u64;
+
+	println!("\nPassed\t\t: {passed}/{}", passed + failed);
+	println!("Skipped\t\t: {skipped}");
+	println!("Total Time (ms)\t: {}", total_elapsed.as_millis());
+	println!("Nodes searched\t: {total_nodes}");
+	println!("Nodes/second\t: {nodes_per_seconds}");
+
+	if failed > 0 {
+		process::exit(1);
+	}
+}
+
+/// Runs the perft counts of a suite entry, returning its mismatches with the nodes searched and
+/// the time taken, or `None` when it has no count within `max_depth`.
+fn suite_entry(
+	chess: &mut Chess,
+	epd: &Epd,
+	max_depth: Option<u8>,
+	threads: usize,
+	bytes: Option<usize>,
+) -> Option<(Vec<String>, u64, Duration)> {
+	let counts = epd
+		.perft_counts()
+		.into_iter()
+		.filter(|&(depth, _)| max_depth.is_none_or(|max_depth| depth <= max_depth))
+		.collect::<Vec<_>>();
+
+	if counts.is_empty() {
+		return None;
+	}
+
+	let mut mismatches = Vec::new();
+	let mut total_nodes = 0;
+	let mut total_elapsed = Duration::ZERO;
+
+	chess
+		.set_position(&epd.board.to_fen())
+		.expect("the board of a parsed EPD record has a valid FEN");
+
+	for (depth, expected) in counts {
+		let report = chess.perft(depth, threads, bytes);
+		let nodes = report.nodes as u64;
+
+		total_nodes += nodes;
+		total_elapsed += report.elapsed;
+
+		if nodes != expected {
+			mismatches.push(format!("D{depth}: expected {expected}, found {nodes}"));
+		}
+	}
+
+	Some((mismatches, total_nodes, total_elapsed))
+}
+
+pub fn book_build(pgn: PathBuf, out: PathBuf, max_ply: usize, min_games: u32) {
+	let reader = match fs::File::open(&pgn) {
+		Ok(file) => PgnReader::new(BufReader::new(file)),
+		Err(error) => exit_with_error(&pgn, error),
+	};
+
+	let mut builder = BookBuilder::new(max_ply, min_games);
+	let mut games = 0;
+	let mut skipped = 0;
+
+	for (index, game) in reader.enumerate() {
+		match game {
+			Ok(game) => {
+				builder.add_game(&game);
+				games += 1;
+			}
+			Err(error) => {
+				println!(
+					"\x1b[33m\x1b[1mWarning:\x1b[0m Skipped game {}: {error}",
+					index + 1
+				);
+				skipped += 1;
+			}
+		}
+	}
+
+	let entries = fs::File::create(&out).and_then(|file| {
+		let mut writer = BufWriter::new(file);
+		let entries = builder.write(&mut writer)?;
+		writer.flush()?;
+
+		Ok(entries)
+	});
+
+	let entries = entries.unwrap_or_else(|error| exit_with_error(&out, error));
+
+	println!("Games read\t: {games}");
+	println!("Games skipped\t: {skipped}");
+	println!("Positions\t: {}", builder.positions());
+	println!("Book entries\t: {entries}");
+}
+
+pub fn retrograde_solve(material: Material, out: PathBuf) {
+	let mut solver = Solver::default();
+	let start = Instant::now();
+	let table = solver.solve(&material);
+	let elapsed = start.elapsed();
+
+	let written = fs::File::create(&out).and_then(|file| {
+		let mut writer = BufWriter::new(file);
+		table.write(&mut writer)?;
+		writer.flush()
+	});
+
+	if let Err(error) = written {
+		exit_with_error(&out, error);
+	}
+
+	println!("Table\t\t: {}", table.material());
+	println!("Positions\t: {}", table.len());
+	println!("Longest mate\t: {} plies", table.max_plies());
+	println!("Time (ms)\t: {}", elapsed.as_millis());
+}
+
+pub fn retrograde_probe(table: PathBuf, board: Box<Board>) {
+	let file = TableFile::open(&table).unwrap_or_else(|error| exit_with_error(&table, error));
+
+	match file.probe(&board) {
+		Some(dtm @ Dtm::Win(plies)) => println!("Mate in {} ({plies} plies)", dtm.moves().unwrap()),
+		Some(dtm @ Dtm::Loss(plies)) => {
+			println!("Mated in {} ({plies} plies)", dtm.moves().unwrap())
+		}
+		Some(Dtm::Draw) => println!("Draw"),
+		None => exit_with_error(
+			&table,
+			format!(
+				"the table is for {}, not the position's pieces",
+				file.material()
+			),
+		),
+	}
+}
+
+fn exit_with_error(path: &Path, error: impl fmt::Display) -> ! {
+	println!("\x1b[31m\x1b[1mError:\x1b[0m {}: {error}", path.display());
+	process::exit(1);
+}
+
 #[cfg(debug_assertions)]
 pub fn magic(piece: MagicPiece) {
 	use chess::{
@@ -123,3 +575,41 @@ fn to_bytes(size: String) -> Option<usize> {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SUITE: &str = "\
+rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - ;D1 20 ;D2 400 ;D3 8902 ;D4 197281
+r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - ;D1 48 ;D2 2039 ;D3 97862
+8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - ;D1 14 ;D2 191 ;D3 2812 ;D4 43238 ;D5 674624
+4k3/8/8/8/8/8/8/R3K1NR w KQ - ;D1 26 ;D5 602333
+";
+
+	#[test]
+	fn suite_passes_with_and_without_hashing() {
+		let mut chess = Chess::default();
+
+		for line in SUITE.lines() {
+			let epd = line.parse::<Epd>().unwrap();
+
+			for bytes in [None, Some(1 << 20)] {
+				let (mismatches, _, _) = suite_entry(&mut chess, &epd, None, 1, bytes).unwrap();
+
+				assert!(mismatches.is_empty(), "{line} {bytes:?}: {mismatches:?}");
+			}
+		}
+	}
+	#[test]
+	fn entries_without_counts_are_skipped() {
+		let mut chess = Chess::default();
+		let without_counts = "4k3/8/8/8/8/8/8/4K3 w - - ;id \"kings\""
+			.parse::<Epd>()
+			.unwrap();
+		let too_deep = "4k3/8/8/8/8/8/8/4K3 w - - ;D4 1000".parse::<Epd>().unwrap();
+
+		assert!(suite_entry(&mut chess, &without_counts, None, 1, None).is_none());
+		assert!(suite_entry(&mut chess, &too_deep, Some(3), 1, None).is_none());
+	}
+}
@@ -0,0 +1,175 @@
+use chess::Chess;
+use std::{
+	collections::BTreeMap,
+	io::{self, BufRead, BufReader, Write},
+	process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+/// Perft node counts by root move, in long algebraic notation.
+pub type Divide = BTreeMap<String, usize>;
+
+/// Reads `move: count` lines, ignoring everything else.
+pub fn parse(text: &str) -> Divide {
+	text.lines()
+		.filter_map(|line| {
+			let (m, count) = line.split_once(':')?;
+			let m = m.trim();
+
+			if !(4..=5).contains(&m.len()) || !m.chars().all(|ch| ch.is_ascii_alphanumeric()) {
+				return None;
+			}
+
+			Some((m.to_string(), count.trim().parse().ok()?))
+		})
+		.collect()
+}
+
+/// An engine that answers `go perft <depth>` with divide output, such as Stockfish.
+pub struct ReferenceEngine {
+	child: Child,
+	stdin: ChildStdin,
+	stdout: BufReader<ChildStdout>,
+}
+
+impl ReferenceEngine {
+	pub fn spawn(command: &str) -> io::Result<Self> {
+		let mut parts = command.split_whitespace();
+		let program = parts
+			.next()
+			.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty engine command"))?;
+
+		let mut child = Command::new(program)
+			.args(parts)
+			.stdin(Stdio::piped())
+			.stdout(Stdio::piped())
+			.spawn()?;
+
+		let stdin = child.stdin.take().unwrap();
+		let stdout = BufReader::new(child.stdout.take().unwrap());
+
+		Ok(Self {
+			child,
+			stdin,
+			stdout,
+		})
+	}
+
+	pub fn divide(&mut self, fen: &str, depth: u8) -> io::Result<Divide> {
+		writeln!(self.stdin, "position fen {fen}")?;
+		writeln!(self.stdin, "go perft {depth}")?;
+		self.stdin.flush()?;
+
+		let mut text = String::new();
+
+		loop {
+			let mut line = String::new();
+
+			if self.stdout.read_line(&mut line)? == 0 {
+				return Err(io::Error::new(
+					io::ErrorKind::UnexpectedEof,
+					"reference engine exited before finishing perft",
+				));
+			}
+
+			if line.starts_with("Nodes searched") {
+				return Ok(parse(&text));
+			}
+
+			text += &line;
+		}
+	}
+}
+
+impl Drop for ReferenceEngine {
+	fn drop(&mut self) {
+		let _ = writeln!(self.stdin, "quit");
+		let _ = self.stdin.flush();
+		let _ = self.child.wait();
+	}
+}
+
+/// Compares our divide output with the reference, descending into the first move whose count
+/// differs until a position with a missing or extra move is found. Deeper references come from
+/// the engine; without one the search stops after the first level. Returns whether all counts
+/// matched.
+pub fn compare(
+	mut chess: Chess,
+	mut depth: u8,
+	threads: usize,
+	bytes: Option<usize>,
+	mut reference: Divide,
+	mut engine: Option<ReferenceEngine>,
+) -> io::Result<bool> {
+	let mut path = Vec::new();
+
+	loop {
+		let ours = chess
+			.perft(depth, threads, bytes)
+			.moves
+			.into_iter()
+			.map(|(m, nodes)| (m.to_string(), (m, nodes)))
+			.collect::<BTreeMap<_, _>>();
+
+		let missing = reference
+			.iter()
+			.filter(|(m, _)| !ours.contains_key(*m))
+			.collect::<Vec<_>>();
+		let extra = ours
+			.iter()
+			.filter(|(m, _)| !reference.contains_key(*m))
+			.collect::<Vec<_>>();
+
+		if !missing.is_empty() || !extra.is_empty() {
+			println!("\nPosition\t: {}", chess.board.to_fen());
+			println!("Moves\t\t: {}", path.join(" "));
+
+			for (m, nodes) in missing {
+				println!("Missing move\t: {m} (reference {nodes})");
+			}
+
+			for (m, (_, nodes)) in extra {
+				println!("Extra move\t: {m} (found {nodes})");
+			}
+
+			return Ok(false);
+		}
+
+		let mismatch = ours.iter().find(|(m, (_, nodes))| reference[*m] != *nodes);
+
+		let Some((text, (m, nodes))) = mismatch else {
+			match path.is_empty() {
+				true => println!("All {} moves match at depth {depth}", ours.len()),
+				false => println!(
+					"All moves after {} match, but the reference totals disagree",
+					path.join(" ")
+				),
+			}
+
+			return Ok(path.is_empty());
+		};
+
+		println!(
+			"Depth {depth}\t: {text} found {nodes}, reference {}",
+			reference[text]
+		);
+
+		chess.play_move(*m);
+		path.push(text.clone());
+		depth -= 1;
+
+		let fen = chess.board.to_fen();
+
+		reference = match engine.as_mut() {
+			Some(engine) => engine.divide(&fen, depth)?,
+			None => {
+				println!("\nPosition\t: {fen}");
+				println!("Moves\t\t: {}", path.join(" "));
+				println!(
+					"Run the reference at depth {depth} on this position, or pass --reference-engine to descend automatically"
+				);
+
+				return Ok(false);
+			}
+		};
+	}
+}
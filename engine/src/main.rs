@@ -1,22 +1,75 @@
 #![allow(dead_code, unused_variables, unused_mut, unused_imports)]
 
 mod args;
+mod divide;
+mod uci;
 
-use args::{Args, Command};
+use args::{Args, BookCommand, Command, RetrogradeCommand};
 use clap::{CommandFactory, Parser};
+use std::io::{self, IsTerminal};
+use uci::Uci;
 
 fn main() {
 	let args = Args::parse();
 
 	match args.command {
+		None if !io::stdin().is_terminal() => Uci::run(),
 		None => Args::command().print_help().unwrap(),
 		Some(Command::Display { fen, bitboards }) => args::display(fen, bitboards),
+		Some(Command::Perft {
+			depth,
+			fen,
+			threads,
+			stats: true,
+			..
+		}) => args::perft_stats(depth, fen, threads),
+		Some(Command::Perft {
+			depth,
+			fen,
+			threads,
+			hash,
+			scaling: true,
+			..
+		}) => args::perft_scaling(depth, fen, threads, hash),
 		Some(Command::Perft {
 			depth,
 			fen,
 			threads,
 			hash,
+			compare: None,
+			reference_engine: None,
+			..
 		}) => args::perft(depth, fen, threads, hash),
+		Some(Command::Perft {
+			depth,
+			fen,
+			threads,
+			hash,
+			compare,
+			reference_engine,
+			..
+		}) => args::perft_compare(depth, fen, threads, hash, compare, reference_engine),
+		Some(Command::PerftSuite {
+			file,
+			depth,
+			threads,
+			hash,
+		}) => args::perft_suite(file, depth, threads, hash),
+		Some(Command::Book {
+			command: BookCommand::Build {
+				pgn,
+				out,
+				max_ply,
+				min_games,
+			},
+		}) => args::book_build(pgn, out, max_ply, min_games),
+		Some(Command::Retrograde {
+			command: RetrogradeCommand::Solve { material, out },
+		}) => args::retrograde_solve(material, out),
+		Some(Command::Retrograde {
+			command: RetrogradeCommand::Probe { table, fen },
+		}) => args::retrograde_probe(table, fen),
+		Some(Command::Uci) => Uci::run(),
 		#[cfg(debug_assertions)]
 		Some(Command::Magic { piece }) => args::magic(piece),
 	}
@@ -0,0 +1,408 @@
+use chess::{
+	board::{color::ColorUtils, Color},
+	book::{Book, Selection},
+	search::{SearchLimits, SearchUtils},
+	tablebase::Tablebase,
+	Chess,
+};
+use std::{
+	env,
+	io::{self, BufRead},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+	thread::{self, JoinHandle},
+	time::Duration,
+};
+
+const NAME: &str = "Yes Another Chess";
+const AUTHOR: &str = "Destin Ecarma";
+const MOVES_TO_GO: u64 = 30;
+const MOVE_OVERHEAD: u64 = 50;
+const START_POSITION: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+const DEFAULT_HASH_MB: usize = 16;
+const MAX_HASH_MB: usize = 65_536;
+
+#[derive(Debug, Default, Clone)]
+pub struct GoOptions {
+	pub wtime: Option<u64>,
+	pub btime: Option<u64>,
+	pub winc: Option<u64>,
+	pub binc: Option<u64>,
+	pub movestogo: Option<u64>,
+	pub depth: Option<u8>,
+	pub nodes: Option<u64>,
+	pub movetime: Option<u64>,
+	pub infinite: bool,
+}
+
+impl GoOptions {
+	fn parse(tokens: &[&str]) -> Self {
+		let mut options = Self::default();
+		let mut tokens = tokens.iter();
+
+		while let Some(token) = tokens.next() {
+			let mut value = || tokens.next().and_then(|value| value.parse::<u64>().ok());
+
+			match *token {
+				"wtime" => options.wtime = value(),
+				"btime" => options.btime = value(),
+				"winc" => options.winc = value(),
+				"binc" => options.binc = value(),
+				"movestogo" => options.movestogo = value(),
+				"depth" => options.depth = value().map(|depth| depth.min(u8::MAX as u64) as u8),
+				"nodes" => options.nodes = value(),
+				"movetime" => options.movetime = value(),
+				"infinite" => options.infinite = true,
+				_ => {}
+			}
+		}
+
+		options
+	}
+
+	fn limits(&self, color: Color) -> SearchLimits {
+		let mut limits = SearchLimits {
+			depth: self.depth,
+			nodes: self.nodes,
+			time: self.movetime.map(Duration::from_millis),
+		};
+
+		let (time, increment) = match color {
+			ColorUtils::WHITE => (self.wtime, self.winc),
+			_ => (self.btime, self.binc),
+		};
+
+		if let (None, Some(time)) = (limits.time, time) {
+			let moves_to_go = self.movestogo.unwrap_or(MOVES_TO_GO).max(1);
+			let budget = time / moves_to_go + increment.unwrap_or(0) / 2;
+
+			limits.time = Some(Duration::from_millis(
+				budget.min(time.saturating_sub(MOVE_OVERHEAD)).max(1),
+			));
+		}
+
+		if self.infinite {
+			limits = SearchLimits::default();
+		}
+
+		limits
+	}
+}
+
+pub struct Uci {
+	chess: Chess,
+	stop: Arc<AtomicBool>,
+	search: Option<JoinHandle<()>>,
+	own_book: bool,
+	book: Option<Book>,
+}
+
+impl Default for Uci {
+	fn default() -> Self {
+		let mut chess = Chess::default();
+		chess.set_hash_size(DEFAULT_HASH_MB * 1024 * 1024);
+		chess.init_bitbases();
+
+		Self {
+			chess,
+			stop: Arc::new(AtomicBool::new(false)),
+			search: None,
+			own_book: false,
+			book: None,
+		}
+	}
+}
+
+impl Uci {
+	pub fn run() {
+		let mut uci = Self::default();
+
+		for line in io::stdin().lock().lines() {
+			let Ok(line) = line else {
+				break;
+			};
+
+			if !uci.handle(&line) {
+				break;
+			}
+		}
+
+		uci.stop_search();
+	}
+
+	/// Handles a single line of input, returning `false` once the engine should quit.
+	fn handle(&mut self, line: &str) -> bool {
+		let tokens = line.split_whitespace().collect::<Vec<&str>>();
+
+		match tokens.first().copied() {
+			Some("uci") => {
+				println!("id name {NAME}");
+				println!("id author {AUTHOR}");
+				println!(
+					"option name Hash type spin default {DEFAULT_HASH_MB} min 1 max {MAX_HASH_MB}"
+				);
+				println!("option name OwnBook type check default false");
+				println!("option name BookFile type string default <empty>");
+				println!("option name SyzygyPath type string default <empty>");
+				println!("uciok");
+			}
+			Some("isready") => println!("readyok"),
+			Some("ucinewgame") => {
+				self.stop_search();
+				self.chess.set_position(START_POSITION).unwrap();
+				self.chess.clear_hash();
+			}
+			Some("position") => {
+				self.stop_search();
+				self.position(&tokens[1..]);
+			}
+			Some("go") => {
+				self.stop_search();
+				self.go(GoOptions::parse(&tokens[1..]));
+			}
+			Some("stop") => self.stop_search(),
+			Some("setoption") => self.set_option(&tokens[1..]),
+			Some("quit") => return false,
+			Some("d") => println!("{}", self.chess),
+			Some(command) => println!("info string Unknown command: {command}"),
+			None => {}
+		}
+
+		true
+	}
+
+	fn position(&mut self, tokens: &[&str]) {
+		let moves_index = tokens.iter().position(|token| *token == "moves");
+
+		let (position, moves) = match moves_index {
+			Some(index) => (&tokens[..index], &tokens[index + 1..]),
+			None => (tokens, &[][..]),
+		};
+
+		let fen = match position.first().copied() {
+			Some("startpos") => START_POSITION.to_string(),
+			Some("fen") => position[1..].join(" "),
+			_ => {
+				println!("info string Invalid position command");
+				return;
+			}
+		};
+
+		let mut chess = self.chess.clone();
+
+		if let Err(error) = chess.set_position(&fen) {
+			println!("info string Invalid FEN: {error}");
+			return;
+		}
+
+		if let Err(issues) = chess.validate() {
+			for issue in issues {
+				println!("info string Invalid position: {issue}");
+			}
+
+			return;
+		}
+
+		for text in moves {
+			match chess.parse_uci_move(text) {
+				Ok(m) => chess.make_move(m),
+				Err(error) => {
+					println!("info string {error}");
+					return;
+				}
+			}
+		}
+
+		self.chess = chess;
+	}
+
+	fn go(&mut self, options: GoOptions) {
+		let book_move = match (&self.book, self.own_book && !options.infinite) {
+			(Some(book), true) => book.pick(&self.chess, Selection::Weighted),
+			_ => None,
+		};
+
+		if let Some(m) = book_move {
+			println!("bestmove {m}");
+			return;
+		}
+
+		let chess = self.chess.clone();
+		let stop = self.stop.clone();
+
+		stop.store(false, Ordering::SeqCst);
+
+		self.search = Some(thread::spawn(move || think(chess, options, stop)));
+	}
+
+	fn set_option(&mut self, tokens: &[&str]) {
+		let value_index = tokens.iter().position(|token| *token == "value");
+
+		let (name, value) = match value_index {
+			Some(index) => (tokens[1..index].join(" "), tokens[index + 1..].join(" ")),
+			None => (tokens[1..].join(" "), String::new()),
+		};
+
+		match name.to_lowercase().as_str() {
+			"hash" => match value.parse::<usize>() {
+				Ok(mb) if (1..=MAX_HASH_MB).contains(&mb) => {
+					self.stop_search();
+					self.chess.set_hash_size(mb * 1024 * 1024);
+				}
+				_ => println!("info string Invalid Hash value: {value}"),
+			},
+			"ownbook" => match value.as_str() {
+				"true" => self.own_book = true,
+				"false" => self.own_book = false,
+				_ => println!("info string Invalid OwnBook value: {value}"),
+			},
+			"bookfile" => match value.as_str() {
+				"" | "<empty>" => self.book = None,
+				path => match Book::open(path) {
+					Ok(book) => self.book = Some(book),
+					Err(error) => {
+						self.book = None;
+						println!("info string Cannot open book {path}: {error}");
+					}
+				},
+			},
+			"syzygypath" => {
+				self.stop_search();
+				self.set_syzygy_path(&value);
+			}
+			_ => println!("info string Unknown option: {name} {value}"),
+		}
+	}
+
+	/// Loads the tables of the directories in `value`, separated like the `PATH` variable.
+	fn set_syzygy_path(&mut self, value: &str) {
+		if matches!(value, "" | "<empty>") {
+			self.chess.set_tablebase(None);
+			return;
+		}
+
+		let mut tablebase = Tablebase::default();
+		let mut files = 0;
+
+		for path in env::split_paths(value) {
+			match tablebase.add_directory(&path) {
+				Ok(count) => files += count,
+				Err(error) => println!(
+					"info string Cannot read tablebase directory {}: {error}",
+					path.display()
+				),
+			}
+		}
+
+		println!(
+			"info string Found {files} tablebase files, up to {} pieces",
+			tablebase.max_pieces()
+		);
+
+		self.chess.set_tablebase((files > 0).then_some(tablebase));
+	}
+
+	/// Stops a running search and waits for it to report its best move, so that a command
+	/// arriving during `go infinite` does not block the input loop.
+	fn stop_search(&mut self) {
+		self.stop.store(true, Ordering::SeqCst);
+
+		if let Some(handle) = self.search.take() {
+			handle.join().unwrap();
+		}
+	}
+}
+
+fn think(mut chess: Chess, options: GoOptions, stop: Arc<AtomicBool>) {
+	let limits = options.limits(chess.board.color);
+
+	let result = chess.search(limits, stop.clone(), |result| {
+		let elapsed = result.elapsed.as_millis();
+		let nps = (result.nodes as u128 * 1000) / elapsed.max(1);
+
+		let score = match SearchUtils::mate_in(result.score) {
+			Some(moves) => format!("mate {moves}"),
+			None => format!("cp {}", result.score),
+		};
+
+		let pv = result
+			.pv
+			.iter()
+			.map(|m| m.to_string())
+			.collect::<Vec<_>>()
+			.join(" ");
+
+		println!(
+			"info depth {} score {score} nodes {} nps {nps} hashfull {} tbhits {} time {elapsed} pv {pv}",
+			result.depth, result.nodes, result.hashfull, result.tb_hits
+		);
+	});
+
+	if options.infinite {
+		while !stop.load(Ordering::SeqCst) {
+			thread::sleep(Duration::from_millis(1));
+		}
+	}
+
+	match result.best_move {
+		Some(m) => println!("bestmove {m}"),
+		None => println!("bestmove 0000"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn position(uci: &mut Uci, command: &str) -> String {
+		let tokens = command.split_whitespace().collect::<Vec<_>>();
+		uci.position(&tokens);
+
+		uci.chess.board.to_fen()
+	}
+
+	#[test]
+	fn invalid_moves_keep_the_position() {
+		let mut uci = Uci::default();
+		let after_e4 = position(&mut uci, "startpos moves e2e4");
+
+		assert_eq!(
+			after_e4,
+			"rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+		);
+		assert_eq!(
+			position(&mut uci, "startpos moves d2d4 e7e5 e1e3"),
+			after_e4
+		);
+		assert_eq!(position(&mut uci, "fen 8/8/8/8 w - - 0 1"), after_e4);
+	}
+	#[test]
+	fn commands_stop_an_infinite_search() {
+		let (sender, receiver) = std::sync::mpsc::channel();
+
+		thread::spawn(move || {
+			let mut uci = Uci::default();
+
+			for command in [
+				"go infinite",
+				"position startpos moves e2e4",
+				"go infinite",
+				"go depth 1",
+			] {
+				uci.handle(command);
+			}
+
+			uci.stop_search();
+			sender.send(uci.chess.board.to_fen()).unwrap();
+		});
+
+		let fen = receiver.recv_timeout(Duration::from_secs(60)).unwrap();
+
+		assert_eq!(
+			fen,
+			"rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+		);
+	}
+}